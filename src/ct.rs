@@ -0,0 +1,38 @@
+// 秘密のデータに依存した分岐をしないための補助関数
+use std::hint::black_box;
+use primitive_types::U256;
+
+// choiceが真なら全ビット1、偽なら0のマスク
+// black_boxでコンパイラが分岐に戻すのを防ぐ
+pub fn mask(choice: bool) -> u64 {
+    0u64.wrapping_sub(black_box(choice as u64))
+}
+
+// choiceが真ならb、偽ならa
+pub fn select_u256(a: U256, b: U256, choice: bool) -> U256 {
+    let m = mask(choice);
+    let mut res = [0u64; 4];
+    for (i, limb) in res.iter_mut().enumerate() {
+        *limb = a.0[i] ^ (m & (a.0[i] ^ b.0[i]));
+    }
+    U256(res)
+}
+
+// choiceが真ならaとbを入れ替える
+pub fn swap_u256(a: &mut U256, b: &mut U256, choice: bool) {
+    let m = mask(choice);
+    for i in 0..4 {
+        let t = m & (a.0[i] ^ b.0[i]);
+        a.0[i] ^= t;
+        b.0[i] ^= t;
+    }
+}
+
+// 全ビットを見てから比較する
+pub fn eq_u256(a: U256, b: U256) -> bool {
+    let mut diff = 0u64;
+    for i in 0..4 {
+        diff |= a.0[i] ^ b.0[i];
+    }
+    black_box(diff) == 0
+}
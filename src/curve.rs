@@ -0,0 +1,179 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use primitive_types::U256;
+use crate::field_element::{ExpressionError, Field, FieldElement, PrimeField};
+use crate::point::Point;
+use crate::prime::is_prime;
+use crate::secp256k1::{GX, GY, N, P};
+
+// 曲線のパラメータを整数で表したもの (体の表現に依らない)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CurveParams {
+    pub name: String,
+    pub p: U256,
+    pub a: U256,
+    pub b: U256,
+    pub gx: U256,
+    pub gy: U256,
+    pub n: U256,
+    pub h: U256,
+}
+
+impl CurveParams {
+    pub fn secp256k1() -> Self {
+        CurveParams {
+            name: "secp256k1".to_string(),
+            p: P,
+            a: U256::zero(),
+            b: U256::from(7),
+            gx: GX,
+            gy: GY,
+            n: N,
+            h: U256::one(),
+        }
+    }
+
+    // 本の例題で使う F_223 上の y^2 = x^3 + 7 (点の数は252 = 7 * 36)
+    pub fn book_f223() -> Self {
+        CurveParams {
+            name: "book-f223".to_string(),
+            p: U256::from(223),
+            a: U256::zero(),
+            b: U256::from(7),
+            gx: U256::from(15),
+            gy: U256::from(86),
+            n: U256::from(7),
+            h: U256::from(36),
+        }
+    }
+}
+
+// 生成点 (x, y) とその位数n、補因子h
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Generator<F: Field> {
+    pub x: F,
+    pub y: F,
+    pub n: U256,
+    pub h: U256,
+}
+
+// 体F上の楕円曲線 y^2 = x^3 + ax + b
+// 点は曲線をArcで共有し、a, bを個別には持たない
+#[derive(Debug, Clone, PartialEq)]
+pub struct Curve<F: Field> {
+    pub name: String,
+    pub a: F,
+    pub b: F,
+    pub generator: Option<Generator<F>>,
+}
+
+impl<F: Field> Curve<F> {
+    pub fn new(name: impl Into<String>, a: F, b: F) -> Result<Self, ExpressionError> {
+        if Self::discriminant(a, b)?.is_zero() { return Err(ExpressionError::SingularCurve) }
+
+        Ok(Curve { name: name.into(), a, b, generator: None })
+    }
+
+    // 判別式 4a^3 + 27b^2 (0なら特異点 (尖点や結節点) を持つ)
+    // 標数が小さい場合もあるので、4と27は1を足し合わせて作る
+    pub fn discriminant(a: F, b: F) -> Result<F, ExpressionError> {
+        let small = |k: u64| (0..k).try_fold(a.zero(), |acc, _| acc.try_add(a.one()));
+        small(4)?.try_mul(a.square().try_mul(a)?)?.try_add(small(27)?.try_mul(b.square())?)
+    }
+
+    pub fn with_generator(mut self, x: F, y: F, n: U256, h: U256) -> Result<Self, ExpressionError> {
+        if !self.contains(x, y)? { return Err(ExpressionError::InvalidPoint) }
+        if n.is_zero() || h.is_zero() { return Err(ExpressionError::InvalidGeneratorOrder) }
+
+        // スカラー倍はnで還元するので、nG = 0 でなければ以後の計算がすべて狂う
+        // 生成点を持たない曲線の上で計算すれば、nは還元されない
+        self.generator = None;
+        let bare = Arc::new(self);
+        if !(&Point::new(x, y, &bare)? * n)?.is_infinity() {
+            return Err(ExpressionError::InvalidGeneratorOrder);
+        }
+
+        let mut curve = Arc::unwrap_or_clone(bare);
+        curve.generator = Some(Generator { x, y, n, h });
+        Ok(curve)
+    }
+
+    // 体の標数
+    pub fn p(&self) -> U256 {
+        self.a.characteristic()
+    }
+
+    pub fn n(&self) -> Option<U256> {
+        self.generator.map(|g| g.n)
+    }
+
+    pub fn h(&self) -> Option<U256> {
+        self.generator.map(|g| g.h)
+    }
+
+    // y^2 = x^3 + ax + b を満たすか (異なる体の元ならErr)
+    pub fn contains(&self, x: F, y: F) -> Result<bool, ExpressionError> {
+        let rhs = x.square().try_mul(x)?.try_add(self.a.try_mul(x)?)?.try_add(self.b)?;
+        Ok(y.square().try_sub(rhs)?.is_zero())
+    }
+
+    // 同じ式の曲線か (名前や生成点は比べない)
+    pub fn same_equation(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b
+    }
+}
+
+impl<F: PrimeField> Curve<F> {
+    pub fn from_params(params: &CurveParams) -> Result<Self, ExpressionError> {
+        if !is_prime(params.p) { return Err(ExpressionError::CompositeModulus) }
+
+        let elem = |n: U256| F::from_u256(n, params.p);
+        Curve::new(params.name.clone(), elem(params.a)?, elem(params.b)?)?
+            .with_generator(elem(params.gx)?, elem(params.gy)?, params.n, params.h)
+    }
+}
+
+// 名前で引ける曲線の一覧
+// secp256k1 と book-f223 は最初から登録されている
+#[derive(Debug, Clone)]
+pub struct CurveRegistry {
+    curves: BTreeMap<String, CurveParams>,
+}
+
+impl CurveRegistry {
+    pub fn new() -> Self {
+        let mut curves = BTreeMap::new();
+        for params in [CurveParams::secp256k1(), CurveParams::book_f223()] {
+            curves.insert(params.name.clone(), params);
+        }
+        CurveRegistry { curves }
+    }
+
+    // 同じ名前の曲線があれば置き換えて、元のパラメータを返す
+    pub fn register(&mut self, params: CurveParams) -> Result<Option<CurveParams>, ExpressionError> {
+        // pが素数か、特異でないか、生成点が曲線上にあって nG = 0 かを任意の大きさの体で確かめる
+        Curve::<FieldElement<U256>>::from_params(&params)?;
+
+        Ok(self.curves.insert(params.name.clone(), params))
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CurveParams> {
+        self.curves.get(name)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.curves.keys().map(String::as_str)
+    }
+
+    // 体の表現Fを選んで曲線を作る
+    pub fn curve<F: PrimeField>(&self, name: &str) -> Result<Arc<Curve<F>>, ExpressionError> {
+        let params = self.get(name).ok_or(ExpressionError::UnknownCurve)?;
+        Ok(Arc::new(Curve::from_params(params)?))
+    }
+}
+
+impl Default for CurveRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+use crate::curve::Curve;
+use crate::field_element::{ExpressionError, Field};
+use crate::field_int::FieldInt;
+use crate::point::Point;
+use crate::prime::is_prime;
+use crate::rng::XorShift64;
+use crate::zn::ZnElement;
+
+// レンストラの楕円曲線法 (ECM) でnの非自明な約数を探す
+// ランダムな曲線上の点に b1 以下の素数冪をすべて掛けていき、
+// 途中で逆元が存在しなくなったらその分母とnの最大公約数が約数になる
+// nが素数の場合や、curves個の曲線で見つからなかった場合はNone
+pub fn ecm<T: FieldInt>(n: T, b1: u32, curves: usize, rng: &mut XorShift64) -> Option<T> {
+    if n < T::from_u64(4) || is_prime(n) { return None }
+    if n.to_u256().low_u64() & 1 == 0 { return Some(T::from_u64(2)) }
+
+    let prime_powers = prime_powers(b1);
+
+    for _ in 0..curves {
+        match try_curve(n, &prime_powers, rng) {
+            Ok(()) => continue,
+            Err(ExpressionError::NonInvertible(d)) => return Some(T::from_u256(d)),
+            // 分母がnの倍数になった場合など、この曲線では見つからない
+            Err(_) => continue,
+        }
+    }
+    None
+}
+
+// 約数が見つかった場合はErr(NonInvertible)を返す
+fn try_curve<T: FieldInt>(n: T, prime_powers: &[u32], rng: &mut XorShift64) -> Result<(), ExpressionError> {
+    let random = |rng: &mut XorShift64| ZnElement::new(T::from_u256(rng.below(n.to_u256())), n);
+
+    // 点 (x, y) を先に決めて、それを通るように b = y^2 - x^3 - ax とする
+    let (x, y, a) = (random(rng)?, random(rng)?, random(rng)?);
+    let b = y.square().try_sub(x.square().try_mul(x)?)?.try_sub(a.try_mul(x)?)?;
+
+    // 判別式が可逆でなければ特異な曲線 (か約数が見つかった)
+    // 0でないことはここで確かめたので、Curve::new で計算し直さない
+    Curve::discriminant(a, b)?.try_inv()?;
+    let curve = Arc::new(Curve { name: "ecm".into(), a, b, generator: None });
+    let mut point = Point::new(x, y, &curve)?;
+
+    for &q in prime_powers {
+        point = (&point * q)?;
+        if point.is_infinity() { break }
+    }
+    Ok(())
+}
+
+// b1以下の各素数qについて、b1を超えない最大の冪 q^e
+fn prime_powers(b1: u32) -> Vec<u32> {
+    let limit = b1 as usize;
+    let mut sieve = vec![true; limit + 1];
+    let mut powers = Vec::new();
+
+    for q in 2..=limit {
+        if !sieve[q] { continue }
+        for m in (q * q..=limit).step_by(q) { sieve[m] = false }
+
+        let mut qe = q as u32;
+        while let Some(next) = qe.checked_mul(q as u32).filter(|&next| next <= b1) {
+            qe = next;
+        }
+        powers.push(qe);
+    }
+    powers
+}
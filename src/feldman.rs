@@ -0,0 +1,61 @@
+use primitive_types::U256;
+use crate::field_element::ExpressionError;
+use crate::point::Point;
+use crate::secp256k1::{self, S256Point, N};
+use crate::shamir::{self, os_random, Share};
+
+// Feldmanの検証可能秘密分散
+// ディーラーは多項式の係数 a_i の代わりに a_i * G を公開し、
+// 各参加者は s_i * G = Σ (i^j * C_j) を確かめることで、自分のシェアが正しいかを検証できる
+// (C_0 = secret * G は秘密鍵に対応する公開鍵になる)
+#[derive(Debug, Clone)]
+pub struct Dealing {
+    pub shares: Vec<Share>,
+    pub commitments: Vec<S256Point>,
+}
+
+// 係数はOSの乱数 (/dev/urandom) から作るので、Unix以外ではErr(RandomUnavailable)になる
+// (その場合はdeal_withで乱数を与える)
+pub fn deal(secret: &[u8; 32], threshold: u8, shares: u8) -> Result<Dealing, ExpressionError> {
+    deal_with(secret, threshold, shares, os_random)
+}
+
+// fillで乱数を与える版 (fillのErrはそのまま返す)
+pub fn deal_with<R: FnMut(&mut [u8; 32]) -> Result<(), ExpressionError>>(secret: &[u8; 32], threshold: u8, shares: u8, fill: R) -> Result<Dealing, ExpressionError> {
+    let poly = shamir::random_polynomial(secret, threshold, shares, fill)?;
+    let g = secp256k1::generator();
+
+    // 係数は秘密なので定数時間の乗算を使う
+    // 最高次の係数が偶然0の場合も、コミットメントはthreshold個にする
+    let commitments = (0..threshold as usize)
+        .map(|i| poly.coeffs().get(i).map_or(Point::infinity(g.curve()), |a| g.mul_ct(a.num())))
+        .collect();
+
+    Ok(Dealing { shares: shamir::deal(&poly, shares)?, commitments })
+}
+
+// s_i * G = Σ (i^j * C_j)
+pub fn verify_share(share: &Share, commitments: &[S256Point]) -> bool {
+    if share.index == 0 || share.value >= N { return false }
+
+    // iとコミットメントは公開されているので、ホーナー法で通常の乗算を使う
+    let index = U256::from(share.index);
+    let expected = commitments.iter().rev()
+        .try_fold(Point::infinity(&secp256k1::curve()), |acc, c| &(&acc * index)? + c);
+
+    // 別の曲線の点が混ざっていれば検証失敗
+    expected.is_ok_and(|expected| secp256k1::generator().mul_ct(share.value) == expected)
+}
+
+// 検証に通ったシェアだけを使って復元する
+// 検証に失敗したシェアがあればErr(InvalidShare)
+pub fn recover(shares: &[Share], commitments: &[S256Point]) -> Result<[u8; 32], ExpressionError> {
+    if commitments.is_empty() || commitments.len() > u8::MAX as usize {
+        return Err(ExpressionError::InvalidThreshold);
+    }
+    if !shares.iter().all(|share| verify_share(share, commitments)) {
+        return Err(ExpressionError::InvalidShare);
+    }
+
+    shamir::recover(shares, commitments.len() as u8)
+}
@@ -1,4 +1,9 @@
-use std::{ops, process::Output};
+use std::collections::BTreeSet;
+use std::ops;
+use std::sync::{Mutex, OnceLock};
+use primitive_types::U256;
+use crate::field_int::FieldInt;
+use crate::prime::is_prime;
 
 
 #[derive(Debug)]
@@ -8,6 +13,18 @@ pub enum ExpressionError {
     DifferentCurves,
     InvalidPoint,
     InvalidFieldElement,
+    EmptyIterator,
+    ZeroDivisionAt(usize),
+    CompositeModulus,
+    NonInvertible(U256),
+    InvalidThreshold,
+    InvalidShare,
+    InconsistentShares,
+    NotEnoughShares,
+    SingularCurve,
+    UnknownCurve,
+    RandomUnavailable(std::io::ErrorKind),
+    InvalidGeneratorOrder,
 }
 
 impl std::error::Error for ExpressionError {}
@@ -20,41 +37,151 @@ impl std::fmt::Display for ExpressionError {
             ExpressionError::DifferentCurves => write!(f, "Points are not on the same curve"),
             ExpressionError::InvalidPoint => write!(f, "Point is not on the curve"),
             ExpressionError::InvalidFieldElement => write!(f, "Num is not in field range 0 to (Order - 1)"),
+            ExpressionError::EmptyIterator => write!(f, "Cannot determine the field of an empty iterator"),
+            ExpressionError::ZeroDivisionAt(i) => write!(f, "Cannot invert zero at index {}", i),
+            ExpressionError::CompositeModulus => write!(f, "Modulus is not a prime"),
+            ExpressionError::NonInvertible(d) => write!(f, "Not invertible: {} divides the modulus", d),
+            ExpressionError::InvalidThreshold => write!(f, "Threshold must be between 1 and the number of shares"),
+            ExpressionError::InvalidShare => write!(f, "Share is malformed or has a bad checksum"),
+            ExpressionError::InconsistentShares => write!(f, "Shares do not lie on the same polynomial"),
+            ExpressionError::NotEnoughShares => write!(f, "Not enough shares to recover the secret"),
+            ExpressionError::SingularCurve => write!(f, "Curve is singular (4a^3 + 27b^2 = 0)"),
+            ExpressionError::UnknownCurve => write!(f, "No curve is registered under that name"),
+            ExpressionError::RandomUnavailable(kind) => write!(f, "Cannot read the OS random source: {}", kind),
+            ExpressionError::InvalidGeneratorOrder => write!(f, "Generator order n does not satisfy nG = 0, or n or h is zero"),
         }
     }
 }
 
-type Order = u32;
-
-pub trait Modulus {
-    fn modulus(self, prime: Order) -> Self;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FieldElement<T: FieldInt> {
+    pub num: T,
+    pub prime: T,
 }
 
-impl Modulus for i32 {
-    fn modulus(self, prime: Order) -> Self {
-        ((self % prime as i32) + prime as i32) % prime as i32
+impl<T: FieldInt> FieldElement<T> { 
+    pub fn new(num: T, prime: T) -> Result<FieldElement<T>, ExpressionError> {
+        // 指数の還元や平方根の計算は法が素数であることを前提にしている
+        // 合成数を法とする環の演算には ZnElement を使う
+        if !is_cached_prime(prime.to_u256()) {
+            return Err(ExpressionError::CompositeModulus);
+        }
+        if num >= prime || num < T::zero() {
+            return Err(ExpressionError::InvalidFieldElement);
+        }
+
+        Ok(FieldElement {
+            num,
+            prime,
+        })
+    }
+
+    // ルジャンドル記号 (a/p) : 0, 1 (平方剰余), -1 (平方非剰余)
+    pub fn legendre(&self) -> i32 {
+        self.num.legendre(self.prime)
+    }
+
+    pub fn is_quadratic_residue(&self) -> bool {
+        self.legendre() != -1
+    }
+
+    pub fn sqrt(&self) -> Option<Self> {
+        Some(Self {
+            num: self.num.sqrt_mod(self.prime)?,
+            prime: self.prime
+        })
     }
 }
 
+// 素数と確認済みの法の数の上限 (超えたら一度空にする)
+const PRIME_CACHE_LIMIT: usize = 256;
+
+// 256bitの素数判定は1回数百µsかかるので、同じ法では結果を使い回す
+// 合成数は覚えない (その場合はErrになるだけなので)
+fn is_cached_prime(prime: U256) -> bool {
+    static PRIMES: OnceLock<Mutex<BTreeSet<U256>>> = OnceLock::new();
+    let primes = PRIMES.get_or_init(Default::default);
+
+    if primes.lock().unwrap_or_else(|e| e.into_inner()).contains(&prime) { return true }
+    if !is_prime(prime) { return false }
+
+    let mut primes = primes.lock().unwrap_or_else(|e| e.into_inner());
+    if primes.len() >= PRIME_CACHE_LIMIT { primes.clear() }
+    primes.insert(prime);
+    true
+}
+
+// 任意の大きさの指数 (符号 + 256bitの絶対値)
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-pub struct FieldElement {
-    pub num: i32,
-    pub prime: Order,
+pub struct Exponent {
+    pub negative: bool,
+    pub magnitude: U256,
 }
 
-impl FieldElement { 
-    pub fn new(num: i32, prime: u32) -> Result<FieldElement, ExpressionError> {
-        if num >= prime as i32 || num < 0 {
-            return Err(ExpressionError::InvalidFieldElement);
-        }
+impl Exponent {
+    // 位数 (p-1) で還元した非負の指数を返す
+    pub fn reduce(self, order: U256) -> U256 {
+        let ex = self.magnitude % order;
+        if self.negative && !ex.is_zero() {
+            // 指数nが負の場合
+            // 指数が正になるまでa^p-1 (= 1) を掛け合わせるので、
+            // a^n = a^(n mod p-1)
+            order - ex
+        } else if ex.is_zero() && !self.magnitude.is_zero() {
+            // 0でない指数が0に還元されると 0^(p-1) が1になってしまうので、p-1のままにする
+            order
+        } else { ex }
+    }
+}
 
-        Ok(FieldElement {
-            num: num,
-            prime: prime,
-        })
+// 二乗と掛け算を上位ビットから繰り返す
+// 各表現のpowはこれに自分の二乗と乗算を渡す
+pub fn pow_by_squaring<T: Copy>(one: T, base: T, ex: U256, square: impl Fn(T) -> T, mul: impl Fn(T, T) -> T) -> T {
+    let mut res = one;
+    for i in (0..ex.bits()).rev() {
+        res = square(res);
+        if ex.bit(i) { res = mul(res, base) }
     }
+    res
+}
+
+// 位数が分からない環や体での冪 (指数は還元しない)
+// 負の指数は逆元の冪で、逆元が存在しなければ0を返す
+pub fn pow_unreduced<F: Field>(base: F, ex: Exponent) -> F {
+    let base = if ex.negative {
+        match base.one().try_div(base) {
+            Ok(inv) => inv,
+            Err(_) => return base.zero(),
+        }
+    } else { base };
+
+    // 同じ環の元同士なので失敗しない
+    pow_by_squaring(base.one(), base, ex.magnitude, |x| x.square(), |x, y| x.try_mul(y).unwrap())
 }
 
+macro_rules! impl_exponent_from_signed {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Exponent {
+            fn from(n: $t) -> Self {
+                Exponent { negative: n < 0, magnitude: U256::from(n.unsigned_abs()) }
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_exponent_from_unsigned {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Exponent {
+            fn from(n: $t) -> Self {
+                Exponent { negative: false, magnitude: U256::from(n) }
+            }
+        }
+    )*};
+}
+
+impl_exponent_from_signed!(i32, i64, i128);
+impl_exponent_from_unsigned!(u32, u64, u128, U256);
+
 pub trait FieldElementOperation {
     type GeneralOpOutput;
 
@@ -62,17 +189,40 @@ pub trait FieldElementOperation {
     fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput;
     fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput;
     fn div_op(self, rhs: Self) -> Self::GeneralOpOutput;
-    fn pow(self, rhs: i32) -> Self;
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self;
+}
+
+// 体の公理に沿った演算
+// 異なる体の元同士の演算はErr(DifferentOrderExpression)を返す
+pub trait Field: FieldElementOperation + Copy + PartialEq + std::fmt::Debug {
+    // selfと同じ体の加法単位元・乗法単位元
+    fn zero(&self) -> Self;
+    fn one(&self) -> Self;
+
+    fn is_zero(&self) -> bool;
+    // 体の標数 (ZnElementでは法n)
+    fn characteristic(&self) -> U256;
+    fn neg(&self) -> Self;
+    // 0の逆元は存在しないのでNone
+    fn inv(&self) -> Option<Self>;
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError>;
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError>;
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError>;
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError>;
+
+    fn square(&self) -> Self;
+    fn double(&self) -> Self;
 }
 
-impl FieldElementOperation for FieldElement {
+impl<T: FieldInt> FieldElementOperation for FieldElement<T> {
     type GeneralOpOutput = Result<Self, ExpressionError>;
 
     fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
         if self.prime != rhs.prime { return Err(ExpressionError::DifferentOrderExpression) }
         
         Ok(Self { 
-            num: (self.num + rhs.num).modulus(self.prime),
+            num: self.num.add_mod(rhs.num, self.prime),
             prime: self.prime
         })
     }
@@ -81,7 +231,7 @@ impl FieldElementOperation for FieldElement {
         if self.prime != rhs.prime { return Err(ExpressionError::DifferentOrderExpression) }
         
         Ok(Self { 
-            num: (self.num - rhs.num).modulus(self.prime),
+            num: self.num.sub_mod(rhs.num, self.prime),
             prime: self.prime
         })
     }
@@ -90,37 +240,124 @@ impl FieldElementOperation for FieldElement {
         if self.prime != rhs.prime { return Err(ExpressionError::DifferentOrderExpression) }
 
         Ok(Self {
-            num: (self.num * rhs.num).modulus(self.prime),
+            num: self.num.mul_mod(rhs.num, self.prime),
             prime: self.prime
         })
     }
 
     fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
         if self.prime != rhs.prime { return Err(ExpressionError::DifferentOrderExpression) }
-        if rhs.num == 0 { return Err(ExpressionError::ZeroDivision) }
+        if rhs.num.is_zero() { return Err(ExpressionError::ZeroDivision) }
 
-        Ok(self.mul_op(rhs.pow(-1))?)
+        self.mul_op(rhs.pow(-1))
     }
 
-    fn pow(self, rhs: i32) -> Self {
-        let ex = if rhs < 0 {
-            // 指数nが負の場合
-            // 指数が正になるまでa^p-1 (= 1) を掛け合わせるので、
-            // a^n = a^(n mod p-1)
-            rhs.modulus(self.prime - 1)
-        } else { rhs };
-
-        let mut num = 1;
-        for _ in 0..ex {num = (self.num * num).modulus(self.prime)}
-        
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
         Self {
-            num: num,
+            num: self.num.pow_mod(rhs.into(), self.prime),
             prime: self.prime
         }
     }
 }
 
-impl ops::Add for FieldElement {
+// 素数pを法とする整数から元を作れる体 (曲線のパラメータを読み込むため)
+// pが素数であることは呼び出し側で確認する
+pub trait PrimeField: Field {
+    fn from_u256(num: U256, prime: U256) -> Result<Self, ExpressionError>;
+}
+
+impl<T: FieldInt> PrimeField for FieldElement<T> {
+    fn from_u256(num: U256, prime: U256) -> Result<Self, ExpressionError> {
+        // Tで表現できない場合
+        let p = T::from_u256(prime);
+        if p.to_u256() != prime || num >= prime { return Err(ExpressionError::InvalidFieldElement) }
+
+        Self::new(T::from_u256(num), p)
+    }
+}
+
+// Montgomeryのトリックによる一括逆元
+// 逆元の計算は1回だけで、残りは 3(n-1) 回の乗算で済む
+pub fn batch_invert<F: Field>(elems: &[F]) -> Result<Vec<F>, ExpressionError> {
+    if let Some(i) = elems.iter().position(|e| e.is_zero()) {
+        return Err(ExpressionError::ZeroDivisionAt(i));
+    }
+    if elems.is_empty() { return Ok(Vec::new()) }
+
+    // prefix[i] = a_0 * a_1 * ... * a_i
+    let mut prefix = Vec::with_capacity(elems.len());
+    let mut acc = elems[0];
+    prefix.push(acc);
+    for &e in &elems[1..] {
+        acc = acc.try_mul(e)?;
+        prefix.push(acc);
+    }
+
+    // (a_0 * ... * a_i)^(-1) から後ろ向きに1つずつ取り出す
+    let mut inv = acc.inv().ok_or(ExpressionError::ZeroDivision)?;
+    let mut res = vec![inv; elems.len()];
+    for i in (1..elems.len()).rev() {
+        res[i] = inv.try_mul(prefix[i - 1])?;
+        inv = inv.try_mul(elems[i])?;
+    }
+    res[0] = inv;
+
+    Ok(res)
+}
+
+impl<T: FieldInt> Field for FieldElement<T> {
+    fn zero(&self) -> Self {
+        Self { num: T::zero(), prime: self.prime }
+    }
+
+    fn one(&self) -> Self {
+        Self { num: T::one(), prime: self.prime }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    fn characteristic(&self) -> U256 {
+        self.prime.to_u256()
+    }
+
+    fn neg(&self) -> Self {
+        Self { num: T::zero().sub_mod(self.num, self.prime), prime: self.prime }
+    }
+
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() { return None }
+
+        Some(self.pow(-1))
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.add_op(rhs)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.sub_op(rhs)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.mul_op(rhs)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.div_op(rhs)
+    }
+
+    fn square(&self) -> Self {
+        Self { num: self.num.mul_mod(self.num, self.prime), prime: self.prime }
+    }
+
+    fn double(&self) -> Self {
+        Self { num: self.num.add_mod(self.num, self.prime), prime: self.prime }
+    }
+}
+
+impl<T: FieldInt> ops::Add for FieldElement<T> {
     type Output = Result<Self, ExpressionError>;
 
     fn add(self, rhs: Self) ->  Self::Output{
@@ -128,7 +365,7 @@ impl ops::Add for FieldElement {
     }
 }
 
-impl ops::Sub for FieldElement {
+impl<T: FieldInt> ops::Sub for FieldElement<T> {
     type Output = Result<Self, ExpressionError>;
 
     fn sub(self, rhs: Self) ->  Self::Output{
@@ -136,7 +373,7 @@ impl ops::Sub for FieldElement {
     }
 }
 
-impl ops::Mul for FieldElement {
+impl<T: FieldInt> ops::Mul for FieldElement<T> {
     type Output = Result<Self, ExpressionError>;
 
     fn mul(self, rhs: Self) -> Self::Output{
@@ -144,7 +381,7 @@ impl ops::Mul for FieldElement {
     }
 }
 
-impl ops::Div for FieldElement {
+impl<T: FieldInt> ops::Div for FieldElement<T> {
     type Output = Result<Self, ExpressionError>;
 
     fn div(self, rhs: Self) -> Self::Output {
@@ -152,4 +389,245 @@ impl ops::Div for FieldElement {
     }
 }
 
+impl<T: FieldInt> ops::Neg for FieldElement<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Field::neg(&self)
+    }
+}
+
+// 参照同士の演算
+macro_rules! impl_field_element_ref_op {
+    ($($op:ident, $method:ident);*) => {$(
+        impl<T: FieldInt> ops::$op<&FieldElement<T>> for FieldElement<T> {
+            type Output = Result<Self, ExpressionError>;
+
+            fn $method(self, rhs: &Self) -> Self::Output {
+                ops::$op::$method(self, *rhs)
+            }
+        }
+
+        impl<T: FieldInt> ops::$op<FieldElement<T>> for &FieldElement<T> {
+            type Output = Result<FieldElement<T>, ExpressionError>;
+
+            fn $method(self, rhs: FieldElement<T>) -> Self::Output {
+                ops::$op::$method(*self, rhs)
+            }
+        }
+
+        impl<T: FieldInt> ops::$op<&FieldElement<T>> for &FieldElement<T> {
+            type Output = Result<FieldElement<T>, ExpressionError>;
+
+            fn $method(self, rhs: &FieldElement<T>) -> Self::Output {
+                ops::$op::$method(*self, *rhs)
+            }
+        }
+    )*};
+}
+
+impl_field_element_ref_op!(Add, add; Sub, sub; Mul, mul; Div, div);
+
+// 代入演算は結果を返せないので、異なる体の元や0での割り算はpanicする
+macro_rules! impl_field_element_assign_op {
+    ($($op:ident, $method:ident, $base:ident);*) => {$(
+        impl<T: FieldInt> ops::$op for FieldElement<T> {
+            fn $method(&mut self, rhs: Self) {
+                *self = self.$base(rhs).unwrap_or_else(|e| panic!("{}", e));
+            }
+        }
+
+        impl<T: FieldInt> ops::$op<&FieldElement<T>> for FieldElement<T> {
+            fn $method(&mut self, rhs: &Self) {
+                *self = self.$base(*rhs).unwrap_or_else(|e| panic!("{}", e));
+            }
+        }
+    )*};
+}
+
+impl_field_element_assign_op!(AddAssign, add_assign, add_op; SubAssign, sub_assign, sub_op; MulAssign, mul_assign, mul_op; DivAssign, div_assign, div_op);
+
+impl<T: FieldInt> num_traits::Inv for FieldElement<T> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn inv(self) -> Self::Output {
+        Field::inv(&self).ok_or(ExpressionError::ZeroDivision)
+    }
+}
+
+impl<T: FieldInt, E: Into<Exponent>> num_traits::Pow<E> for FieldElement<T> {
+    type Output = Self;
+
+    fn pow(self, rhs: E) -> Self::Output {
+        FieldElementOperation::pow(self, rhs)
+    }
+}
+
+impl<T: FieldInt> FieldElement<T> {
+    // 体の位数が分からないので、空のイテレータの和や積はErr(EmptyIterator)を返す
+    pub fn try_sum<I: IntoIterator<Item = Self>>(iter: I) -> Result<Self, ExpressionError> {
+        let mut iter = iter.into_iter();
+        let first = iter.next().ok_or(ExpressionError::EmptyIterator)?;
+        iter.try_fold(first, |acc, n| acc + n)
+    }
+
+    pub fn try_product<I: IntoIterator<Item = Self>>(iter: I) -> Result<Self, ExpressionError> {
+        let mut iter = iter.into_iter();
+        let first = iter.next().ok_or(ExpressionError::EmptyIterator)?;
+        iter.try_fold(first, |acc, n| acc * n)
+    }
+}
+
+// 空のイテレータや異なる体の元が混ざっている場合はErrになる
+// iter.sum::<Result<_, _>>()? と書ける
+impl<T: FieldInt> std::iter::Sum<FieldElement<T>> for Result<FieldElement<T>, ExpressionError> {
+    fn sum<I: Iterator<Item = FieldElement<T>>>(iter: I) -> Self {
+        FieldElement::try_sum(iter)
+    }
+}
+
+impl<'a, T: FieldInt> std::iter::Sum<&'a FieldElement<T>> for Result<FieldElement<T>, ExpressionError> {
+    fn sum<I: Iterator<Item = &'a FieldElement<T>>>(iter: I) -> Self {
+        FieldElement::try_sum(iter.copied())
+    }
+}
+
+impl<T: FieldInt> std::iter::Product<FieldElement<T>> for Result<FieldElement<T>, ExpressionError> {
+    fn product<I: Iterator<Item = FieldElement<T>>>(iter: I) -> Self {
+        FieldElement::try_product(iter)
+    }
+}
+
+impl<'a, T: FieldInt> std::iter::Product<&'a FieldElement<T>> for Result<FieldElement<T>, ExpressionError> {
+    fn product<I: Iterator<Item = &'a FieldElement<T>>>(iter: I) -> Self {
+        FieldElement::try_product(iter.copied())
+    }
+}
+
+// 位数が型や定数で決まる体 (S256Field, Fp) のnum-traits・演算子の実装
+// 型は ZERO, ONE と FieldElementOperation (GeneralOpOutput = Self) を持つ必要がある
+macro_rules! impl_field_num_traits {
+    ([$($gen:tt)*] $ty:ty) => {
+        impl<$($gen)*> std::ops::Neg for $ty {
+            type Output = Self;
+
+            fn neg(self) -> Self::Output {
+                $crate::field_element::Field::neg(&self)
+            }
+        }
+
+        // num_traits::Num のためだけの実装
+        // 体では割り算が常に割り切れるので余りは0 (0で割った場合は整数と同様にpanicする)
+        impl<$($gen)*> std::ops::Rem for $ty {
+            type Output = Self;
+
+            fn rem(self, rhs: Self) -> Self::Output {
+                if $crate::field_element::Field::is_zero(&rhs) {
+                    panic!("{}", $crate::field_element::ExpressionError::ZeroDivision);
+                }
+                Self::ZERO
+            }
+        }
+
+        $crate::field_element::impl_field_num_traits!(@op [$($gen)*] $ty; Add, add, AddAssign, add_assign, add_op);
+        $crate::field_element::impl_field_num_traits!(@op [$($gen)*] $ty; Sub, sub, SubAssign, sub_assign, sub_op);
+        $crate::field_element::impl_field_num_traits!(@op [$($gen)*] $ty; Mul, mul, MulAssign, mul_assign, mul_op);
+        $crate::field_element::impl_field_num_traits!(@op [$($gen)*] $ty; Div, div, DivAssign, div_assign, div_op);
+
+        impl<$($gen)*> num_traits::Zero for $ty {
+            fn zero() -> Self {
+                Self::ZERO
+            }
+
+            fn is_zero(&self) -> bool {
+                *self == Self::ZERO
+            }
+        }
+
+        impl<$($gen)*> num_traits::One for $ty {
+            fn one() -> Self {
+                Self::ONE
+            }
+        }
+
+        impl<$($gen)*> num_traits::Inv for $ty {
+            type Output = Self;
+
+            fn inv(self) -> Self::Output {
+                Self::ONE / self
+            }
+        }
+
+        impl<E: Into<$crate::field_element::Exponent>, $($gen)*> num_traits::Pow<E> for $ty {
+            type Output = Self;
+
+            fn pow(self, rhs: E) -> Self::Output {
+                $crate::field_element::FieldElementOperation::pow(self, rhs)
+            }
+        }
+
+        impl<$($gen)*> std::iter::Sum for $ty {
+            fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::ZERO, |acc, n| acc + n)
+            }
+        }
+
+        impl<'a, $($gen)*> std::iter::Sum<&'a $ty> for $ty {
+            fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::ZERO, |acc, n| acc + n)
+            }
+        }
+
+        impl<$($gen)*> std::iter::Product for $ty {
+            fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+                iter.fold(Self::ONE, |acc, n| acc * n)
+            }
+        }
+
+        impl<'a, $($gen)*> std::iter::Product<&'a $ty> for $ty {
+            fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+                iter.fold(Self::ONE, |acc, n| acc * n)
+            }
+        }
+    };
+
+    (@op [$($gen:tt)*] $ty:ty; $op:ident, $method:ident, $assign_op:ident, $assign_method:ident, $base:ident) => {
+        impl<$($gen)*> std::ops::$op<&$ty> for $ty {
+            type Output = Self;
+
+            fn $method(self, rhs: &Self) -> Self::Output {
+                $crate::field_element::FieldElementOperation::$base(self, *rhs)
+            }
+        }
+
+        impl<$($gen)*> std::ops::$op<$ty> for &$ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: $ty) -> Self::Output {
+                $crate::field_element::FieldElementOperation::$base(*self, rhs)
+            }
+        }
+
+        impl<$($gen)*> std::ops::$op<&$ty> for &$ty {
+            type Output = $ty;
+
+            fn $method(self, rhs: &$ty) -> Self::Output {
+                $crate::field_element::FieldElementOperation::$base(*self, *rhs)
+            }
+        }
+
+        impl<$($gen)*> std::ops::$assign_op for $ty {
+            fn $assign_method(&mut self, rhs: Self) {
+                *self = $crate::field_element::FieldElementOperation::$base(*self, rhs);
+            }
+        }
+
+        impl<$($gen)*> std::ops::$assign_op<&$ty> for $ty {
+            fn $assign_method(&mut self, rhs: &Self) {
+                *self = $crate::field_element::FieldElementOperation::$base(*self, *rhs);
+            }
+        }
+    };
+}
 
+pub(crate) use impl_field_num_traits;
@@ -0,0 +1,155 @@
+use std::fmt::Debug;
+use primitive_types::U256;
+use crate::field_element::{pow_by_squaring, Exponent};
+
+// 有限体の元を表現する整数型
+// 引数はすべて 0 <= n < m に還元済みであることを前提にする
+pub trait FieldInt: Copy + Debug + PartialEq + Eq + PartialOrd + Ord {
+    fn zero() -> Self;
+    fn one() -> Self;
+    fn from_u64(n: u64) -> Self;
+    // nが表現できる範囲にあることは呼び出し側で保証する
+    fn from_u256(n: U256) -> Self;
+    fn to_u256(self) -> U256;
+
+    fn add_mod(self, rhs: Self, m: Self) -> Self;
+    fn sub_mod(self, rhs: Self, m: Self) -> Self;
+    fn mul_mod(self, rhs: Self, m: Self) -> Self;
+
+    fn is_zero(&self) -> bool {
+        *self == Self::zero()
+    }
+
+    fn pow_mod(self, ex: Exponent, m: Self) -> Self {
+        let ex = ex.reduce(m.to_u256() - 1);
+        pow_by_squaring(Self::one(), self, ex, |x| x.mul_mod(x, m), |x, y| x.mul_mod(y, m))
+    }
+
+    // ルジャンドル記号 (a/p) : 0, 1 (平方剰余), -1 (平方非剰余)
+    fn legendre(self, m: Self) -> i32 {
+        if self.is_zero() { return 0 }
+        if m == Self::from_u64(2) { return 1 }
+
+        // オイラーの規準 a^((p-1)/2) = ±1
+        if self.pow_mod(((m.to_u256() - 1) >> 1).into(), m) == Self::one() { 1 } else { -1 }
+    }
+
+    fn sqrt_mod(self, m: Self) -> Option<Self> {
+        if self.is_zero() || m == Self::from_u64(2) { return Some(self) }
+        if self.legendre(m) != 1 { return None }
+
+        let p = m.to_u256();
+        if p.low_u64() & 3 == 3 {
+            // p = 3 (mod 4) の場合は a^((p+1)/4) が平方根になる
+            return Some(self.pow_mod(((p + 1) >> 2).into(), m));
+        }
+
+        // Tonelli-Shanks
+        // p - 1 = q * 2^s (qは奇数)
+        let mut s = (p - 1).trailing_zeros();
+        let q = (p - 1) >> s;
+
+        // 平方非剰余zを探す
+        let z = (2..).map(Self::from_u64).find(|n| n.legendre(m) == -1)?;
+
+        let mut c = z.pow_mod(q.into(), m);
+        let mut t = self.pow_mod(q.into(), m);
+        let mut r = self.pow_mod(((q >> 1) + 1).into(), m);
+
+        while t != Self::one() {
+            // t^(2^i) = 1 となる最小のiを探す
+            let mut i = 0;
+            let mut t_pow = t;
+            while t_pow != Self::one() {
+                t_pow = t_pow.mul_mod(t_pow, m);
+                i += 1;
+            }
+
+            let mut b = c;
+            for _ in 0..(s - i - 1) { b = b.mul_mod(b, m) }
+            s = i;
+            c = b.mul_mod(b, m);
+            t = t.mul_mod(c, m);
+            r = r.mul_mod(b, m);
+        }
+
+        Some(r)
+    }
+}
+
+// 倍の幅の整数型で計算してから還元する
+macro_rules! impl_field_int_widening {
+    ($($t:ty => $wide:ty),*) => {$(
+        impl FieldInt for $t {
+            fn zero() -> Self { 0 }
+            fn one() -> Self { 1 }
+            fn from_u64(n: u64) -> Self { n as $t }
+            fn from_u256(n: U256) -> Self { n.low_u64() as $t }
+            fn to_u256(self) -> U256 { U256::from(self as u64) }
+
+            fn add_mod(self, rhs: Self, m: Self) -> Self {
+                ((self as $wide + rhs as $wide) % m as $wide) as $t
+            }
+
+            fn sub_mod(self, rhs: Self, m: Self) -> Self {
+                ((self as $wide + m as $wide - rhs as $wide) % m as $wide) as $t
+            }
+
+            fn mul_mod(self, rhs: Self, m: Self) -> Self {
+                ((self as $wide * rhs as $wide) % m as $wide) as $t
+            }
+        }
+    )*};
+}
+
+impl_field_int_widening!(i32 => i64, i64 => i128, u32 => u64, u64 => u128);
+
+impl FieldInt for u128 {
+    fn zero() -> Self { 0 }
+    fn one() -> Self { 1 }
+    fn from_u64(n: u64) -> Self { n as u128 }
+    fn from_u256(n: U256) -> Self { n.low_u128() }
+    fn to_u256(self) -> U256 { U256::from(self) }
+
+    fn add_mod(self, rhs: Self, m: Self) -> Self {
+        // a + b < 2m なので、桁あふれかmを超えた場合はmを一度引けば良い
+        let (sum, overflow) = self.overflowing_add(rhs);
+        if overflow || sum >= m { sum.wrapping_sub(m) } else { sum }
+    }
+
+    fn sub_mod(self, rhs: Self, m: Self) -> Self {
+        let (diff, borrow) = self.overflowing_sub(rhs);
+        if borrow { diff.wrapping_add(m) } else { diff }
+    }
+
+    fn mul_mod(self, rhs: Self, m: Self) -> Self {
+        // 積は最大256bitになるので、U256で計算してから還元する
+        (U256::from(self) * U256::from(rhs) % U256::from(m)).low_u128()
+    }
+}
+
+impl FieldInt for U256 {
+    fn zero() -> Self { U256::zero() }
+    fn one() -> Self { U256::one() }
+    fn from_u64(n: u64) -> Self { U256::from(n) }
+    fn from_u256(n: U256) -> Self { n }
+    fn to_u256(self) -> U256 { self }
+
+    fn add_mod(self, rhs: Self, m: Self) -> Self {
+        // a + b < 2m なので、2^256を超えたかmを超えた場合はmを一度引けば良い
+        let (sum, overflow) = self.overflowing_add(rhs);
+        if overflow || sum >= m { sum.overflowing_sub(m).0 } else { sum }
+    }
+
+    fn sub_mod(self, rhs: Self, m: Self) -> Self {
+        // 桁借りが発生した場合はmを足して戻す
+        let (diff, borrow) = self.overflowing_sub(rhs);
+        if borrow { diff.overflowing_add(m).0 } else { diff }
+    }
+
+    fn mul_mod(self, rhs: Self, m: Self) -> Self {
+        // 積は最大512bitになるので、U512で計算してから還元する
+        let product = self.full_mul(rhs) % primitive_types::U512::from(m);
+        U256::try_from(product).unwrap()
+    }
+}
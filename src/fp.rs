@@ -0,0 +1,185 @@
+use std::ops;
+use primitive_types::U256;
+use crate::field_element::{impl_field_num_traits, ExpressionError, Exponent, Field, FieldElementOperation, PrimeField};
+use crate::field_int::FieldInt;
+use crate::prime::is_prime_u64;
+
+// 位数Pを型に持つ有限体の元
+// 異なる体の元同士の演算や、Pが素数でない場合はコンパイル時にエラーになる
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Fp<const P: u64> {
+    pub num: u64,
+}
+
+impl<const P: u64> Fp<P> {
+    // 定数はコンパイル時に評価されるので、Pが素数でなければ (P < 2 も含む) ここでエラーになる
+    pub const PRIME: u64 = {
+        assert!(is_prime_u64(P), "Fp<P> requires a prime P");
+        P
+    };
+    pub const ZERO: Self = Self::checked(0);
+    pub const ONE: Self = Self::checked(1);
+
+    const fn checked(num: u64) -> Self {
+        Fp { num: num % Self::PRIME }
+    }
+
+    pub fn new(num: u64) -> Result<Self, ExpressionError> {
+        if num >= Self::PRIME {
+            return Err(ExpressionError::InvalidFieldElement);
+        }
+
+        Ok(Fp { num })
+    }
+
+    // ルジャンドル記号 (a/p) : 0, 1 (平方剰余), -1 (平方非剰余)
+    pub fn legendre(&self) -> i32 {
+        self.num.legendre(P)
+    }
+
+    pub fn is_quadratic_residue(&self) -> bool {
+        self.legendre() != -1
+    }
+
+    pub fn sqrt(&self) -> Option<Self> {
+        Some(Fp { num: self.num.sqrt_mod(P)? })
+    }
+}
+
+impl<const P: u64> FieldElementOperation for Fp<P> {
+    type GeneralOpOutput = Self;
+
+    fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        Fp { num: self.num.add_mod(rhs.num, P) }
+    }
+
+    fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        Fp { num: self.num.sub_mod(rhs.num, P) }
+    }
+
+    fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        Fp { num: self.num.mul_mod(rhs.num, P) }
+    }
+
+    // 結果を返せないので、0で割った場合はpanicする (Errが必要ならtry_divを使う)
+    fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if rhs.is_zero() { panic!("{}", ExpressionError::ZeroDivision) }
+
+        self.mul_op(rhs.pow(-1))
+    }
+
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
+        Fp { num: self.num.pow_mod(rhs.into(), P) }
+    }
+}
+
+impl<const P: u64> Field for Fp<P> {
+    fn zero(&self) -> Self {
+        Self::ZERO
+    }
+
+    fn one(&self) -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    fn characteristic(&self) -> U256 {
+        U256::from(P)
+    }
+
+    fn neg(&self) -> Self {
+        Fp { num: 0.sub_mod(self.num, P) }
+    }
+
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() { return None }
+
+        Some(self.pow(-1))
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.add_op(rhs))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.sub_op(rhs))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.mul_op(rhs))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        if rhs.is_zero() { return Err(ExpressionError::ZeroDivision) }
+
+        Ok(self.div_op(rhs))
+    }
+
+    fn square(&self) -> Self {
+        self.mul_op(*self)
+    }
+
+    fn double(&self) -> Self {
+        self.add_op(*self)
+    }
+}
+
+impl<const P: u64> PrimeField for Fp<P> {
+    fn from_u256(num: U256, prime: U256) -> Result<Self, ExpressionError> {
+        if prime != U256::from(Self::PRIME) { return Err(ExpressionError::DifferentOrderExpression) }
+        if num >= prime { return Err(ExpressionError::InvalidFieldElement) }
+
+        Ok(Fp { num: num.low_u64() })
+    }
+}
+
+impl<const P: u64> ops::Add for Fp<P> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.add_op(rhs)
+    }
+}
+
+impl<const P: u64> ops::Sub for Fp<P> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_op(rhs)
+    }
+}
+
+impl<const P: u64> ops::Mul for Fp<P> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_op(rhs)
+    }
+}
+
+impl<const P: u64> ops::Div for Fp<P> {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_op(rhs)
+    }
+}
+
+impl_field_num_traits!([const P: u64] Fp<P>);
+
+impl<const P: u64> num_traits::Num for Fp<P> {
+    type FromStrRadixErr = std::num::ParseIntError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        Ok(Fp { num: u64::from_str_radix(str, radix)? % P })
+    }
+}
+
+impl<const P: u64> std::fmt::Display for Fp<P> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.num)
+    }
+}
@@ -0,0 +1,226 @@
+use std::ops;
+use primitive_types::U256;
+use crate::field_element::{pow_unreduced, ExpressionError, Exponent, Field, FieldElementOperation};
+use crate::poly::Polynomial;
+
+// 体F上のK次拡大体 F[x] / (m(x)) の元 c_0 + c_1 x + ... + c_{K-1} x^{K-1}
+// m(x) = x^K + m_{K-1} x^{K-1} + ... + m_0 はモニックな既約多項式で、
+// modulusには最高次以外の係数 [m_0, ..., m_{K-1}] を持つ
+// 既約性は確認しないので、可約な多項式を与えるとinvがNoneになる元が現れる
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FpK<F: Field, const K: usize> {
+    pub coeffs: [F; K],
+    pub modulus: [F; K],
+}
+
+// 2次拡大体
+pub type Fp2<F> = FpK<F, 2>;
+
+impl<F: Field, const K: usize> FpK<F, K> {
+    pub fn new(coeffs: [F; K], modulus: [F; K]) -> Result<Self, ExpressionError> {
+        // 係数がすべて同じ体の元であることを確認する
+        let base = modulus[0];
+        for c in coeffs.iter().chain(modulus.iter()) {
+            base.try_add(*c)?;
+        }
+
+        Ok(FpK { coeffs, modulus })
+    }
+
+    // Fの元をそのまま埋め込む
+    pub fn from_base(c: F, modulus: [F; K]) -> Result<Self, ExpressionError> {
+        let mut coeffs = [c.zero(); K];
+        coeffs[0] = c;
+        Self::new(coeffs, modulus)
+    }
+
+    fn with_coeffs(&self, coeffs: [F; K]) -> Self {
+        FpK { coeffs, modulus: self.modulus }
+    }
+
+    fn check_modulus(&self, rhs: &Self) -> Result<(), ExpressionError> {
+        if self.modulus != rhs.modulus { return Err(ExpressionError::DifferentOrderExpression) }
+        Ok(())
+    }
+
+    fn zip_coeffs(&self, rhs: &Self, op: impl Fn(F, F) -> Result<F, ExpressionError>) -> Result<Self, ExpressionError> {
+        self.check_modulus(rhs)?;
+
+        let mut coeffs = self.coeffs;
+        for (c, &r) in coeffs.iter_mut().zip(rhs.coeffs.iter()) {
+            *c = op(*c, r)?;
+        }
+        Ok(self.with_coeffs(coeffs))
+    }
+
+    // 多項式として掛けてから x^K = -(m_{K-1} x^{K-1} + ... + m_0) で次数を下げる
+    fn mul_reduce(&self, rhs: &Self) -> Result<Self, ExpressionError> {
+        self.check_modulus(rhs)?;
+
+        let zero = self.coeffs[0].zero();
+        let mut prod = vec![zero; 2 * K - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                prod[i + j] = prod[i + j].try_add(a.try_mul(b)?)?;
+            }
+        }
+        for d in (K..2 * K - 1).rev() {
+            let top = prod[d];
+            for (i, &m) in self.modulus.iter().enumerate() {
+                prod[d - K + i] = prod[d - K + i].try_sub(top.try_mul(m)?)?;
+            }
+        }
+
+        let mut coeffs = [zero; K];
+        coeffs.copy_from_slice(&prod[..K]);
+        Ok(self.with_coeffs(coeffs))
+    }
+
+    // 多項式の拡張ユークリッドの互除法で a(x) s(x) + m(x) t(x) = c (定数) となる s(x) / c を求める
+    fn try_inv(&self) -> Result<Self, ExpressionError> {
+        if self.is_zero() { return Err(ExpressionError::ZeroDivision) }
+
+        let one = self.coeffs[0].one();
+        let mut m = self.modulus.to_vec();
+        m.push(one);
+
+        let (mut r0, mut r1) = (Polynomial::new(m), Polynomial::new(self.coeffs.to_vec()));
+        let (mut s0, mut s1) = (Polynomial::zero(), Polynomial::constant(one));
+        while !r1.is_zero() {
+            let (q, r) = r0.divrem(&r1)?;
+            let s = s0.try_sub(&q.try_mul(&s1)?)?;
+            (r0, r1) = (r1, r);
+            (s0, s1) = (s1, s);
+        }
+
+        // m(x) が既約なら gcd は定数になる
+        if r0.degree() != Some(0) { return Err(ExpressionError::ZeroDivision) }
+        let c_inv = r0.coeffs()[0].inv().ok_or(ExpressionError::ZeroDivision)?;
+        let s = s0.scale(c_inv)?;
+
+        let mut coeffs = [self.coeffs[0].zero(); K];
+        coeffs[..s.coeffs().len()].copy_from_slice(s.coeffs());
+        Ok(self.with_coeffs(coeffs))
+    }
+}
+
+impl<F: Field, const K: usize> FieldElementOperation for FpK<F, K> {
+    type GeneralOpOutput = Result<Self, ExpressionError>;
+
+    fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        self.zip_coeffs(&rhs, |a, b| a.try_add(b))
+    }
+
+    fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        self.zip_coeffs(&rhs, |a, b| a.try_sub(b))
+    }
+
+    fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        self.mul_reduce(&rhs)
+    }
+
+    fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        self.check_modulus(&rhs)?;
+
+        self.mul_reduce(&rhs.try_inv()?)
+    }
+
+    // 乗法群の位数 p^K - 1 はU256に収まらないことがあるので、指数は還元しない
+    // 負の指数で逆元が存在しない場合は0を返す
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
+        pow_unreduced(self, rhs.into())
+    }
+}
+
+impl<F: Field, const K: usize> Field for FpK<F, K> {
+    fn zero(&self) -> Self {
+        self.with_coeffs([self.coeffs[0].zero(); K])
+    }
+
+    fn one(&self) -> Self {
+        let mut coeffs = [self.coeffs[0].zero(); K];
+        coeffs[0] = self.coeffs[0].one();
+        self.with_coeffs(coeffs)
+    }
+
+    fn is_zero(&self) -> bool {
+        self.coeffs.iter().all(|c| c.is_zero())
+    }
+
+    fn characteristic(&self) -> U256 {
+        self.coeffs[0].characteristic()
+    }
+
+    fn neg(&self) -> Self {
+        self.with_coeffs(self.coeffs.map(|c| c.neg()))
+    }
+
+    fn inv(&self) -> Option<Self> {
+        self.try_inv().ok()
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.add_op(rhs)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.sub_op(rhs)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.mul_op(rhs)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.div_op(rhs)
+    }
+
+    // 同じ体の元同士なので失敗しない
+    fn square(&self) -> Self {
+        self.mul_reduce(self).unwrap()
+    }
+
+    fn double(&self) -> Self {
+        self.with_coeffs(self.coeffs.map(|c| c.double()))
+    }
+}
+
+impl<F: Field, const K: usize> ops::Add for FpK<F, K> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.add_op(rhs)
+    }
+}
+
+impl<F: Field, const K: usize> ops::Sub for FpK<F, K> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_op(rhs)
+    }
+}
+
+impl<F: Field, const K: usize> ops::Mul for FpK<F, K> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_op(rhs)
+    }
+}
+
+impl<F: Field, const K: usize> ops::Div for FpK<F, K> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_op(rhs)
+    }
+}
+
+impl<F: Field, const K: usize> ops::Neg for FpK<F, K> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Field::neg(&self)
+    }
+}
@@ -0,0 +1,29 @@
+use primitive_types::U256;
+
+// 拡張ユークリッドの互除法
+// (g, t) を返す: g = gcd(a, m), a * t = g (mod m)
+// 実行時間が値に依存するので、秘密のデータには使わない
+pub fn ext_gcd(a: U256, m: U256) -> (U256, U256) {
+    let (mut r0, mut r1) = (m, a % m);
+    // aの係数は符号が交互に変わるので、絶対値と符号を分けて持つ
+    // |t| <= m なので U256 に収まる
+    let (mut t0, mut t1) = (U256::zero(), U256::one());
+    let (mut t0_neg, mut t1_neg) = (false, false);
+
+    while !r1.is_zero() {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 + q * t1);
+        (t0_neg, t1_neg) = (t1_neg, !t1_neg);
+    }
+
+    let t = t0 % m;
+    if t0_neg && !t.is_zero() { (r0, m - t) } else { (r0, t) }
+}
+
+// a^(-1) mod m (aとmが互いに素でなければNone)
+pub fn inv_mod(a: U256, m: U256) -> Option<U256> {
+    let (g, t) = ext_gcd(a, m);
+
+    if g == U256::one() { Some(t) } else { None }
+}
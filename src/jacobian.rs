@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use crate::curve::Curve;
+use crate::field_element::{ExpressionError, Field};
+use crate::point::{Coordinates, Point};
+
+// ヤコビアン座標 (X:Y:Z) = (X/Z^2, Y/Z^3)、無限遠点は Z = 0
+// 加算や2倍算で割り算をせず、アフィン座標に戻すときに1回だけ逆元を計算する
+#[derive(Debug)]
+pub struct JacobianPoint<F: Field> {
+    x: F,
+    y: F,
+    z: F,
+    curve: Arc<Curve<F>>,
+}
+
+impl<F: Field> JacobianPoint<F> {
+    pub fn infinity(curve: &Arc<Curve<F>>) -> Self {
+        let one = curve.a.one();
+        JacobianPoint { x: one, y: one, z: one.zero(), curve: Arc::clone(curve) }
+    }
+
+    pub fn from_affine(point: &Point<F>) -> Self {
+        match point.coordinates() {
+            Coordinates::Affine { x, y } => JacobianPoint { x, y, z: x.one(), curve: Arc::clone(point.curve()) },
+            Coordinates::Infinity => Self::infinity(point.curve()),
+        }
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.z.is_zero()
+    }
+
+    pub fn curve(&self) -> &Arc<Curve<F>> {
+        &self.curve
+    }
+
+    // 2倍算 (EFD の dbl-1998-cmo-2)
+    pub fn double(&self) -> Result<Self, ExpressionError> {
+        // 接線が垂直なら無限遠点
+        if self.is_infinity() || self.y.is_zero() { return Ok(Self::infinity(&self.curve)) }
+
+        let xx = self.x.square();
+        let yy = self.y.square();
+        let yyyy = yy.square();
+        let zz = self.z.square();
+
+        // s = 4XY^2, m = 3X^2 + aZ^4 (a = 0 の曲線では省く)
+        let s = self.x.try_mul(yy)?.double().double();
+        let mut m = xx.double().try_add(xx)?;
+        if !self.curve.a.is_zero() {
+            m = m.try_add(self.curve.a.try_mul(zz.square())?)?;
+        }
+
+        let x3 = m.square().try_sub(s.double())?;
+        let y3 = m.try_mul(s.try_sub(x3)?)?.try_sub(yyyy.double().double().double())?;
+        let z3 = self.y.try_mul(self.z)?.double();
+
+        Ok(JacobianPoint { x: x3, y: y3, z: z3, curve: Arc::clone(&self.curve) })
+    }
+
+    // アフィン座標の点との加算 (EFD の madd-2004-hmv)
+    pub fn add_affine(&self, rhs: &Point<F>) -> Result<Self, ExpressionError> {
+        if !self.curve.same_equation(rhs.curve()) {
+            return Err(ExpressionError::DifferentCurves);
+        }
+
+        let (x2, y2) = match rhs.coordinates() {
+            Coordinates::Infinity => return Ok(self.clone()),
+            Coordinates::Affine { x, y } => (x, y),
+        };
+        if self.is_infinity() { return Ok(Self::from_affine(rhs)) }
+
+        // u2 = x2 Z1^2, s2 = y2 Z1^3 で rhs を self と同じ Z に揃える
+        let z1z1 = self.z.square();
+        let u2 = x2.try_mul(z1z1)?;
+        let s2 = y2.try_mul(self.z)?.try_mul(z1z1)?;
+        let h = u2.try_sub(self.x)?;
+        let r = s2.try_sub(self.y)?;
+
+        if h.is_zero() {
+            // 同じ点なら2倍算、y軸対称なら無限遠点
+            return if r.is_zero() { self.double() } else { Ok(Self::infinity(&self.curve)) };
+        }
+
+        let hh = h.square();
+        let hhh = h.try_mul(hh)?;
+        let v = self.x.try_mul(hh)?;
+
+        let x3 = r.square().try_sub(hhh)?.try_sub(v.double())?;
+        let y3 = r.try_mul(v.try_sub(x3)?)?.try_sub(self.y.try_mul(hhh)?)?;
+        let z3 = self.z.try_mul(h)?;
+
+        Ok(JacobianPoint { x: x3, y: y3, z: z3, curve: Arc::clone(&self.curve) })
+    }
+
+    // Zの逆元が存在しない場合はtry_divのErrをそのまま返す (ECMで約数を見つけるため)
+    pub fn to_affine(&self) -> Result<Point<F>, ExpressionError> {
+        if self.is_infinity() { return Ok(Point::infinity(&self.curve)) }
+
+        let z_inv = self.z.one().try_div(self.z)?;
+        let z_inv2 = z_inv.square();
+        let x = self.x.try_mul(z_inv2)?;
+        let y = self.y.try_mul(z_inv2)?.try_mul(z_inv)?;
+
+        Point::new(x, y, &self.curve)
+    }
+}
+
+impl<F: Field> Clone for JacobianPoint<F> {
+    fn clone(&self) -> Self {
+        JacobianPoint { x: self.x, y: self.y, z: self.z, curve: Arc::clone(&self.curve) }
+    }
+}
+
+impl<F: Field> From<&Point<F>> for JacobianPoint<F> {
+    fn from(point: &Point<F>) -> Self {
+        Self::from_affine(point)
+    }
+}
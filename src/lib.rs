@@ -0,0 +1,19 @@
+pub mod ct;
+pub mod curve;
+pub mod ecm;
+pub mod feldman;
+pub mod field_element;
+pub mod field_int;
+pub mod fp;
+pub mod fpk;
+pub mod gcd;
+pub mod hash;
+pub mod jacobian;
+pub mod montgomery;
+pub mod point;
+pub mod poly;
+pub mod prime;
+pub mod rng;
+pub mod secp256k1;
+pub mod shamir;
+pub mod zn;
@@ -1,14 +1,17 @@
-mod field_element;
-use field_element::{FieldElement, FieldElementOperation};
+use programmingbitcoin_rs::ecm::ecm;
+use programmingbitcoin_rs::field_element::{FieldElement, FieldElementOperation};
+use programmingbitcoin_rs::rng::XorShift64;
 
-mod point;
-use point::Point;
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
 
-mod secp256k1;
-use secp256k1::S256Field;
+    match args.first().map(String::as_str) {
+        Some("ecm") => ecm_command(&args[1..]),
+        _ => demo(),
+    }
+}
 
-fn main() {
-    
+fn demo() {
     let a = FieldElement::new(2,13).unwrap();
     let b = FieldElement::new(4,13).unwrap();
     
@@ -17,13 +20,61 @@ fn main() {
     println!("Hello, world!");
 }
 
+// ecm <n> [b1] [curves]
+fn ecm_command(args: &[String]) {
+    let parse = |i: usize, default: Option<u64>| -> u64 {
+        match (args.get(i), default) {
+            (Some(s), _) => s.parse().unwrap_or_else(|e| {
+                eprintln!("invalid argument {:?}: {}", s, e);
+                std::process::exit(2);
+            }),
+            (None, Some(d)) => d,
+            (None, None) => {
+                eprintln!("usage: ecm <n> [b1] [curves]");
+                std::process::exit(2);
+            }
+        }
+    };
+    let n = parse(0, None);
+    let b1 = parse(1, Some(2000)).min(u32::MAX as u64) as u32;
+    let curves = parse(2, Some(200)) as usize;
+
+    match ecm(n, b1, curves, &mut XorShift64::new(n)) {
+        Some(d) => println!("{} = {} * {}", n, d, n / d),
+        None => {
+            println!("no factor of {} found", n);
+            std::process::exit(1);
+        }
+    }
+}
+
 
 #[cfg(test)]
 mod tests {
     use core::panic;
 
-    use crate::field_element::FieldElement;
-    use crate::point::Point;
+    use primitive_types::{U256, U512};
+
+    use programmingbitcoin_rs::field_element::{batch_invert, ExpressionError, Field, FieldElement, FieldElementOperation};
+    use programmingbitcoin_rs::field_int::FieldInt;
+    use programmingbitcoin_rs::gcd::{ext_gcd, inv_mod};
+    use programmingbitcoin_rs::hash::{hash256, sha256};
+    use programmingbitcoin_rs::shamir::{self, Share, SHARE_LEN};
+    use programmingbitcoin_rs::prime::{is_prime, is_prime_u64};
+    use programmingbitcoin_rs::fp::Fp;
+    use programmingbitcoin_rs::fpk::{Fp2, FpK};
+    use programmingbitcoin_rs::montgomery::Montgomery;
+    use std::sync::Arc;
+
+    use programmingbitcoin_rs::curve::{Curve, CurveParams, CurveRegistry};
+    use programmingbitcoin_rs::point::{Coordinates, Point};
+    use programmingbitcoin_rs::jacobian::JacobianPoint;
+    use programmingbitcoin_rs::poly::Polynomial;
+    use programmingbitcoin_rs::zn::ZnElement;
+    use programmingbitcoin_rs::ecm::ecm;
+    use programmingbitcoin_rs::feldman;
+    use programmingbitcoin_rs::rng::XorShift64;
+    use programmingbitcoin_rs::secp256k1::{self, Inversion, S256Field, S256MontField, S256Point, S256Scalar, N, N_MONTGOMERY, P, P_MONTGOMERY};
 
     #[test]
     fn field_element_eq_and_ne() -> Result<(), Box<dyn std::error::Error>> {
@@ -124,6 +175,7 @@ mod tests {
     fn ecc_test_valid_points() -> Result<(), Box<dyn std::error::Error>> {
         let a = FieldElement::new(0, 223)?;
         let b = FieldElement::new(7, 223)?;
+        let curve = Arc::new(Curve::new("book-f223", a, b)?);
 
         let x1 = FieldElement::new(192, 223)?;
         let y1 = FieldElement::new(105, 223)?;
@@ -132,9 +184,9 @@ mod tests {
         let x3 = FieldElement::new(1, 223)?;
         let y3 = FieldElement::new(193, 223)?;
 
-        Point::new(Some(x1), Some(y1), a, b)?;
-        Point::new(Some(x2), Some(y2), a, b)?;
-        Point::new(Some(x3), Some(y3), a, b)?;
+        Point::new(x1, y1, &curve)?;
+        Point::new(x2, y2, &curve)?;
+        Point::new(x3, y3, &curve)?;
 
         Ok(())
     }
@@ -144,18 +196,19 @@ mod tests {
     fn ecc_test_invalid_points() {
         let a = FieldElement::new(0, 223).unwrap();
         let b = FieldElement::new(7, 223).unwrap();
+        let curve = Arc::new(Curve::new("book-f223", a, b).unwrap());
 
         let x1 = FieldElement::new(200, 223).unwrap();
         let y1 = FieldElement::new(119, 223).unwrap();
         let x2 = FieldElement::new(42, 223).unwrap();
         let y2 = FieldElement::new(99, 223).unwrap();
 
-        match Point::new(Some(x1), Some(y1), a, b) {
+        match Point::new(x1, y1, &curve) {
             Ok(_) => println!("why?"),
             Err(_) => panic!("Invalid point"),
         }
 
-        match Point::new(Some(x2), Some(y2), a, b) {
+        match Point::new(x2, y2, &curve) {
             Ok(_) => println!("why?"),
             Err(_) => panic!("Invalid point"),
         }
@@ -165,6 +218,7 @@ mod tests {
     fn ecc_test_add_points() -> Result<(), Box<dyn std::error::Error>> {
         let a = FieldElement::new(0, 223)?;
         let b = FieldElement::new(7, 223)?;
+        let curve = Arc::new(Curve::new("book-f223", a, b)?);
 
         // 加算テスト1
         let x1 = FieldElement::new(170, 223)?;
@@ -174,9 +228,9 @@ mod tests {
         let x3 = FieldElement::new(220, 223)?;
         let y3 = FieldElement::new(181, 223)?;
         
-        let p1 = Point::new(Some(x1), Some(y1), a, b)?;
-        let p2 = Point::new(Some(x2), Some(y2), a, b)?;
-        let p3 = Point::new(Some(x3), Some(y3), a, b)?;
+        let p1 = Point::new(x1, y1, &curve)?;
+        let p2 = Point::new(x2, y2, &curve)?;
+        let p3 = Point::new(x3, y3, &curve)?;
 
         assert_eq!((&p1 + &p2)?, p3);
 
@@ -188,9 +242,9 @@ mod tests {
         let x3 = FieldElement::new(215, 223)?;
         let y3 = FieldElement::new(68, 223)?;
 
-        let p1 = Point::new(Some(x1), Some(y1), a, b)?;
-        let p2 = Point::new(Some(x2), Some(y2), a, b)?;
-        let p3 = Point::new(Some(x3), Some(y3), a, b)?;
+        let p1 = Point::new(x1, y1, &curve)?;
+        let p2 = Point::new(x2, y2, &curve)?;
+        let p3 = Point::new(x3, y3, &curve)?;
 
         assert_eq!((&p1 + &p2)?, p3);
         
@@ -201,11 +255,12 @@ mod tests {
     fn ecc_test_mul_points() -> Result<(), Box<dyn std::error::Error>> {
         let a = FieldElement::new(0, 223)?;
         let b = FieldElement::new(7, 223)?;
+        let curve = Arc::new(Curve::new("book-f223", a, b)?);
 
         let x1 = FieldElement::new(170, 223)?;
         let y1 = FieldElement::new(142, 223)?;
 
-        let p1 = Point::new(Some(x1), Some(y1), a, b)?;
+        let p1 = Point::new(x1, y1, &curve)?;
 
         let p2 = (&p1 * 2)?;
 
@@ -213,4 +268,1458 @@ mod tests {
         
         Ok(())
     }
+
+    #[test]
+    fn s256_field_prime() -> Result<(), Box<dyn std::error::Error>> {
+        let two = U256::from(2);
+        let p = two.pow(U256::from(255)) - two.pow(U256::from(32)) - U256::from(977) + two.pow(U256::from(255));
+
+        assert_eq!(P, p);
+        assert_eq!(S256Field::new(U256::from(1))?.prime, p);
+        // p以上の値は還元せずにErrにする
+        assert!(matches!(S256Field::new(P), Err(ExpressionError::InvalidFieldElement)));
+        assert!(matches!(S256Field::new(U256::MAX), Err(ExpressionError::InvalidFieldElement)));
+        assert_eq!(S256Field::new(P - U256::from(1))? + S256Field::ONE, S256Field::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_field_reduce() -> Result<(), Box<dyn std::error::Error>> {
+        let a = S256Field::new(P - U256::from(1))?;
+        let b = S256Field::new(U256::from(3))?;
+        let c = S256Field::new(U256::from(4))?;
+
+        assert_eq!(a + S256Field::new(U256::from(1))?, S256Field::new(U256::from(0))?);
+        assert_eq!(c - b, S256Field::new(U256::from(1))?);
+        assert_eq!(b * c, S256Field::new(U256::from(12))?);
+        assert_eq!(b.pow(5), S256Field::new(U256::from(243))?);
+
+        Ok(())
+    }
+
+    fn s256_hex(hex: &str) -> S256Field {
+        S256Field::new(U256::from_str_radix(hex, 16).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn s256_field_add_and_sub_without_overflow() -> Result<(), Box<dyn std::error::Error>> {
+        let max = S256Field::new(P - U256::from(1))?;
+        let one = S256Field::new(U256::from(1))?;
+        let zero = S256Field::new(U256::from(0))?;
+
+        assert_eq!(max + max, S256Field::new(P - U256::from(2))?);
+        assert_eq!(zero - one, max);
+        assert_eq!(one - max, S256Field::new(U256::from(2))?);
+
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+
+        assert_eq!(gx + gy, s256_hex("C1F940F620808011B3455E91DC9813AFFFB3B123D4537CF2F63A51EB1208EC50"));
+        assert_eq!(gx - gy, s256_hex("31838C07D338F746F7FB6699C076025E058448928748D4BFBDAAB0CB1BE742E0"));
+        assert_eq!(gy - gx, s256_hex("CE7C73F82CC708B9080499663F89FDA1FA7BB76D78B72B4042554F33E418B94F"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_field_mul_without_overflow() -> Result<(), Box<dyn std::error::Error>> {
+        let max = S256Field::new(P - U256::from(1))?;
+        let one = S256Field::new(U256::from(1))?;
+
+        // (-1) * (-1) = 1
+        assert_eq!(max * max, one);
+
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+
+        assert_eq!(gx * gy, s256_hex("FD3DC529C6EB60FB9D166034CF3C1A5A72324AA9DFD3428A56D7E1CE0179FD9B"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_generator_on_curve() -> Result<(), Box<dyn std::error::Error>> {
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+
+        S256Point::new(gx, gy, &secp256k1::curve())?;
+
+        assert!(S256Point::new(gx, gx, &secp256k1::curve()).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_element_pow_with_large_exponent() -> Result<(), Box<dyn std::error::Error>> {
+        let a = FieldElement::new(3, 31)?;
+        let b = FieldElement::new(24, 31)?;
+
+        // a^(p-1) = 1
+        assert_eq!(a.pow(30u32), FieldElement::new(1, 31)?);
+        assert_eq!(a.pow(U256::MAX), a.pow(U256::MAX % U256::from(30)));
+        assert_eq!(a.pow(-3), a.pow(27));
+        assert_eq!(a.pow(-3i64), a.pow(-33i128));
+        assert_eq!((b.pow(-1) * b)?, FieldElement::new(1, 31)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_field_pow_and_div() -> Result<(), Box<dyn std::error::Error>> {
+        let one = S256Field::new(U256::from(1))?;
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+
+        // フェルマーの小定理による逆元
+        assert_eq!(gx * gx.pow(P - U256::from(2)), one);
+        assert_eq!(gx.pow(-1), gx.pow(P - U256::from(2)));
+        assert_eq!(gx.pow(P - U256::from(1)), one);
+        assert_eq!((gx / gy) * gy, gx);
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_point_add_and_mul() -> Result<(), Box<dyn std::error::Error>> {
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+        let g = S256Point::new(gx, gy, &secp256k1::curve())?;
+
+        let x2 = s256_hex("C6047F9441ED7D6D3045406E95C07CD85C778E4B8CEF3CA7ABAC09B95C709EE5");
+        let y2 = s256_hex("1AE168FEA63DC339A3C58419466CEAEEF7F632653266D0E1236431A950CFE52A");
+        let g2 = S256Point::new(x2, y2, &secp256k1::curve())?;
+
+        assert_eq!((&g + &g)?, g2);
+        assert_eq!((&g * U256::from(2))?, g2);
+        assert_eq!(g, secp256k1::generator());
+
+        // (n-1)G = -G
+        let neg_g = S256Point::new(gx, S256Field::new(P - gy.num)?, &secp256k1::curve())?;
+        assert_eq!((&g * (N - U256::from(1)))?, neg_g);
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_element_sqrt() -> Result<(), Box<dyn std::error::Error>> {
+        // p = 3 (mod 4), p = 5 (mod 8), p = 1 (mod 8) のそれぞれを確認する
+        for prime in [223, 13, 17, 41] {
+            for n in 0..prime {
+                let a = FieldElement::new(n, prime)?;
+
+                match a.sqrt() {
+                    Some(root) => {
+                        assert!(a.is_quadratic_residue());
+                        assert_eq!((root * root)?, a);
+                    },
+                    None => {
+                        assert_eq!(a.legendre(), -1);
+                        assert!(!a.is_quadratic_residue());
+                    },
+                }
+            }
+        }
+
+        assert_eq!(FieldElement::new(0, 13)?.legendre(), 0);
+        assert_eq!(FieldElement::new(4, 13)?.legendre(), 1);
+        assert_eq!(FieldElement::new(2, 13)?.legendre(), -1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_field_sqrt() -> Result<(), Box<dyn std::error::Error>> {
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+        let seven = S256Field::new(U256::from(7))?;
+
+        let root = (gx.pow(3) + seven).sqrt().unwrap();
+        assert!(root == gy || root == -gy);
+        assert_eq!(gy.legendre(), 1);
+
+        // -1 は p = 3 (mod 4) では平方非剰余
+        let minus_one = S256Field::new(P - U256::from(1))?;
+        assert_eq!(minus_one.legendre(), -1);
+        assert_eq!(minus_one.sqrt(), None);
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_element_large_primes() -> Result<(), Box<dyn std::error::Error>> {
+        // 2^61 - 1
+        let p61 = (1u64 << 61) - 1;
+        let a = FieldElement::new(p61 - 1, p61)?;
+        assert_eq!((a * a)?, FieldElement::new(1, p61)?);
+        assert_eq!((a + a)?, FieldElement::new(p61 - 2, p61)?);
+        assert_eq!(((a / FieldElement::new(3, p61)?)? * FieldElement::new(3, p61)?)?, a);
+
+        // 2^127 - 1
+        let p127 = (1u128 << 127) - 1;
+        let b = FieldElement::new(p127 - 2, p127)?;
+        assert_eq!((b * b)?, FieldElement::new(4, p127)?);
+        assert_eq!((FieldElement::new(1, p127)? - b)?, FieldElement::new(3, p127)?);
+        assert_eq!(b.pow(p127 - 1), FieldElement::new(1, p127)?);
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_element_u256_matches_s256_field() -> Result<(), Box<dyn std::error::Error>> {
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+        let x = FieldElement::new(gx.num, P)?;
+        let y = FieldElement::new(gy.num, P)?;
+
+        assert_eq!((x * y)?.num, (gx * gy).num);
+        assert_eq!((x / y)?.num, (gx / gy).num);
+        assert_eq!((x - y)?.num, (gx - gy).num);
+        assert_eq!(y.sqrt().map(|r| r.num), gy.sqrt().map(|r| r.num));
+
+        let a = FieldElement::new(U256::zero(), P)?;
+        let b = FieldElement::new(U256::from(7), P)?;
+        let curve = Arc::new(Curve::new("secp256k1", a, b)?);
+        Point::new(x, y, &curve)?;
+
+        Ok(())
+    }
+
+    #[test]
+    fn fp_arithmetic() -> Result<(), Box<dyn std::error::Error>> {
+        type F13 = Fp<13>;
+
+        let a = F13::new(7)?;
+        let b = F13::new(12)?;
+        let c = F13::new(6)?;
+
+        assert_eq!(a + b, c);
+        assert_eq!(c - b, a);
+        assert_eq!(F13::new(2)? * F13::new(8)?, F13::new(3)?);
+        assert_eq!(F13::new(8)? / F13::new(2)?, F13::new(4)?);
+        assert_eq!(F13::new(2)?.pow(-4) * F13::new(4)?.pow(2), F13::new(1)?);
+        assert_eq!(F13::new(10)?.sqrt().map(|r| r * r), Some(F13::new(10)?));
+        assert!(F13::new(13).is_err());
+        assert_eq!(F13::PRIME, 13);
+
+        Ok(())
+    }
+
+    #[test]
+    fn fp_matches_field_element() -> Result<(), Box<dyn std::error::Error>> {
+        const PRIME: u64 = (1 << 61) - 1;
+
+        let a = Fp::<PRIME>::new(123456789)?;
+        let b = Fp::<PRIME>::new(PRIME - 987654321)?;
+        let fa = FieldElement::new(a.num, PRIME)?;
+        let fb = FieldElement::new(b.num, PRIME)?;
+
+        assert_eq!((a * b).num, (fa * fb)?.num);
+        assert_eq!((a / b).num, (fa / fb)?.num);
+        assert_eq!((a - b).num, (fa - fb)?.num);
+
+        Ok(())
+    }
+
+    #[test]
+    fn field_trait_identities() -> Result<(), Box<dyn std::error::Error>> {
+        let a = FieldElement::new(7u64, 13)?;
+
+        assert_eq!(a.zero(), FieldElement::new(0, 13)?);
+        assert_eq!(a.one(), FieldElement::new(1, 13)?);
+        assert!(a.zero().is_zero());
+        assert_eq!(a.neg(), FieldElement::new(6, 13)?);
+        assert_eq!(a.try_add(a.neg())?, a.zero());
+        assert_eq!(a.try_mul(a.inv().unwrap())?, a.one());
+        assert_eq!(a.zero().inv(), None);
+        assert_eq!(a.square(), FieldElement::new(10, 13)?);
+        assert_eq!(a.double(), FieldElement::new(1, 13)?);
+        assert!(a.try_add(FieldElement::new(1, 17)?).is_err());
+
+        let b = S256Field::new(U256::from(5))?;
+        assert_eq!(b.neg() + b, S256Field::ZERO);
+        assert_eq!(b.inv().unwrap() * b, S256Field::ONE);
+        assert_eq!(S256Field::ZERO.inv(), None);
+        assert_eq!(b.square(), S256Field::new(U256::from(25))?);
+        assert_eq!(b.double(), S256Field::new(U256::from(10))?);
+
+        let c = Fp::<13>::new(7)?;
+        assert_eq!(c.neg(), Fp::<13>::new(6)?);
+        assert_eq!(c.inv().unwrap() * c, Fp::<13>::ONE);
+        assert_eq!(c.square().num, a.square().num);
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_over_fp() -> Result<(), Box<dyn std::error::Error>> {
+        type F223 = Fp<223>;
+
+        let a = F223::ZERO;
+        let b = F223::new(7)?;
+        let curve = Arc::new(Curve::new("book-f223", a, b)?);
+
+        let p1 = Point::new(F223::new(170)?, F223::new(142)?, &curve)?;
+        let p2 = Point::new(F223::new(60)?, F223::new(139)?, &curve)?;
+        let p3 = Point::new(F223::new(220)?, F223::new(181)?, &curve)?;
+
+        assert_eq!((&p1 + &p2)?, p3);
+        assert_eq!((&p1 * 2)?, (&p1 + &p1)?);
+
+        // 位数倍すると無限遠点
+        let g = Point::new(F223::new(15)?, F223::new(86)?, &curve)?;
+        assert_eq!((&g * 7)?, Point::infinity(&curve));
+
+        Ok(())
+    }
+
+    // num-traitsだけに依存する汎用コード
+    fn horner<T: num_traits::Num + Copy>(coefficients: &[T], x: T) -> T {
+        coefficients.iter().rev().fold(T::zero(), |acc, &c| acc * x + c)
+    }
+
+    #[test]
+    fn num_traits_generic_code() -> Result<(), Box<dyn std::error::Error>> {
+        use num_traits::{Inv, Num, One, Pow, Zero};
+
+        // 3 + 2x + x^2 (x = 5)
+        let coefficients = [Fp::<13>::new(3)?, Fp::<13>::new(2)?, Fp::<13>::new(1)?];
+        assert_eq!(horner(&coefficients, Fp::<13>::new(5)?), Fp::<13>::new(38 % 13)?);
+
+        let s = [S256Field::new(U256::from(3))?, S256Field::new(U256::from(2))?, <S256Field as One>::one()];
+        assert_eq!(horner(&s, S256Field::new(U256::from(5))?), S256Field::new(U256::from(38))?);
+
+        assert!(<S256Field as Zero>::is_zero(&<S256Field as Zero>::zero()));
+        assert_eq!(Inv::inv(S256Field::new(U256::from(2))?) * S256Field::new(U256::from(2))?, S256Field::ONE);
+        assert_eq!(Pow::pow(Fp::<13>::new(2)?, 4u32), Fp::<13>::new(3)?);
+        assert_eq!(Fp::<13>::from_str_radix("20", 10)?, Fp::<13>::new(7)?);
+        assert_eq!(S256Field::from_str_radix("ff", 16)?, S256Field::new(U256::from(255))?);
+        assert!(S256Field::from_str_radix("xyz", 10).is_err());
+
+        Ok(())
+    }
+
+    // 参照で演算する汎用コード
+    fn mul_refs<'a, T>(a: &'a T, b: &'a T) -> <&'a T as std::ops::Mul>::Output
+    where
+        &'a T: std::ops::Mul,
+    {
+        a * b
+    }
+
+    fn sub_ref<'a, T: std::ops::Sub<&'a T>>(a: T, b: &'a T) -> T::Output {
+        a - b
+    }
+
+    #[test]
+    fn num_traits_operators() -> Result<(), Box<dyn std::error::Error>> {
+        let a = S256Field::new(U256::from(7))?;
+        let b = S256Field::new(U256::from(3))?;
+
+        let mut c = a;
+        c += b;
+        c -= &b;
+        c *= b;
+        c /= &b;
+        assert_eq!(c, a);
+        assert_eq!(-a + a, S256Field::ZERO);
+        assert_eq!(mul_refs(&a, &b), a * b);
+        assert_eq!(sub_ref(a, &b), a - b);
+
+        let elems = [a, b, S256Field::ONE];
+        assert_eq!(elems.iter().sum::<S256Field>(), S256Field::new(U256::from(11))?);
+        assert_eq!(elems.into_iter().product::<S256Field>(), S256Field::new(U256::from(21))?);
+        assert_eq!(Vec::<Fp<13>>::new().into_iter().sum::<Fp<13>>(), Fp::<13>::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn num_traits_field_element() -> Result<(), Box<dyn std::error::Error>> {
+        use num_traits::Inv;
+
+        let a = FieldElement::new(7, 13)?;
+        let b = FieldElement::new(12, 13)?;
+
+        assert_eq!(-a, FieldElement::new(6, 13)?);
+        assert_eq!(mul_refs(&a, &b)?, FieldElement::new(6, 13)?);
+        assert_eq!(sub_ref(a, &b)?, FieldElement::new(8, 13)?);
+        assert_eq!((Inv::inv(a)? * a)?, FieldElement::new(1, 13)?);
+        assert!(Inv::inv(FieldElement::new(0, 13)?).is_err());
+
+        let mut c = a;
+        c += b;
+        c *= &b;
+        c /= b;
+        c -= b;
+        assert_eq!(c, a);
+
+        let elems = [a, b, FieldElement::new(1, 13)?];
+        assert_eq!(elems.iter().sum::<Result<_, _>>()?, FieldElement::new(7, 13)?);
+        assert_eq!(elems.iter().product::<Result<_, _>>()?, FieldElement::new(6, 13)?);
+        assert_eq!(elems.into_iter().sum::<Result<_, _>>()?, FieldElement::new(7, 13)?);
+        assert!(matches!(Vec::<FieldElement<i32>>::new().into_iter().sum::<Result<_, _>>(), Err(ExpressionError::EmptyIterator)));
+        assert!(matches!(Vec::<FieldElement<i32>>::new().iter().product::<Result<_, _>>(), Err(ExpressionError::EmptyIterator)));
+        assert!(FieldElement::<i32>::try_sum(vec![]).is_err());
+        assert!(FieldElement::try_product(vec![a, FieldElement::new(1, 17)?]).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic]
+    fn field_element_assign_with_different_order() {
+        let mut a = FieldElement::new(7, 13).unwrap();
+        a += FieldElement::new(7, 17).unwrap();
+    }
+
+    // テスト用の疑似乱数 (xorshift64)
+    fn random_u256(state: &mut u64, modulus: U256) -> U256 {
+        let mut limbs = [0u64; 4];
+        for limb in limbs.iter_mut() {
+            *state ^= *state << 13;
+            *state ^= *state >> 7;
+            *state ^= *state << 17;
+            *limb = *state;
+        }
+        U256(limbs) % modulus
+    }
+
+    #[test]
+    fn montgomery_parameters() {
+        let mut state = 0x2545F4914F6CDD1D;
+        for modulus in [P, N] {
+            let mont = Montgomery::new(modulus);
+            for _ in 0..100 {
+                let a = random_u256(&mut state, modulus);
+                assert_eq!(mont.from_montgomery(mont.to_montgomery(a)), a);
+            }
+            assert_eq!(mont.from_montgomery(mont.one()), U256::one());
+        }
+        // R mod p = 2^32 + 977
+        assert_eq!(Montgomery::new(P), P_MONTGOMERY);
+        assert_eq!(P_MONTGOMERY.one(), U256::from(0x1000003D1u64));
+    }
+
+    #[test]
+    fn montgomery_matches_naive() {
+        let mut state = 0x9E3779B97F4A7C15;
+
+        for modulus in [P, N] {
+            let mont = Montgomery::new(modulus);
+
+            for _ in 0..1000 {
+                let a = random_u256(&mut state, modulus);
+                let b = random_u256(&mut state, modulus);
+                let (am, bm) = (mont.to_montgomery(a), mont.to_montgomery(b));
+
+                assert_eq!(mont.from_montgomery(mont.mul(am, bm)), a.mul_mod(b, modulus));
+                assert_eq!(mont.from_montgomery(mont.square(am)), a.mul_mod(a, modulus));
+                assert_eq!(mont.mul_mod(a, b), a.mul_mod(b, modulus));
+            }
+
+            for _ in 0..20 {
+                let a = random_u256(&mut state, modulus);
+                let ex = random_u256(&mut state, U256::MAX);
+                let am = mont.to_montgomery(a);
+
+                assert_eq!(mont.from_montgomery(mont.pow(am, ex)), a.pow_mod(ex.into(), modulus));
+                assert_eq!(
+                    mont.inv(am).map(|i| mont.from_montgomery(i)),
+                    Some(a.pow_mod((modulus - 2).into(), modulus)),
+                );
+            }
+            assert_eq!(mont.inv(U256::zero()), None);
+        }
+
+        // 合成数の法では指数を還元できない: 2^14 mod 15 = 4
+        let mont = Montgomery::new(U256::from(15));
+        let two = mont.to_montgomery(U256::from(2));
+        assert_eq!(mont.from_montgomery(mont.pow(two, U256::from(14))), U256::from(4));
+        assert_eq!(mont.from_montgomery(mont.pow(U256::zero(), U256::from(14))), U256::zero());
+        assert_eq!(mont.pow(two, U256::zero()), mont.one());
+    }
+
+    #[test]
+    fn s256_field_matches_naive() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0xD1B54A32D192ED03;
+
+        for _ in 0..200 {
+            let a = random_u256(&mut state, P);
+            let b = random_u256(&mut state, P);
+            let (sa, sb) = (S256Field::new(a)?, S256Field::new(b)?);
+            let (fa, fb) = (FieldElement::new(a, P)?, FieldElement::new(b, P)?);
+
+            assert_eq!((sa * sb).num, (fa * fb)?.num);
+            assert_eq!(sa.square().num, fa.square().num);
+            assert_eq!((sa / sb).num, (fa / fb)?.num);
+            assert_eq!(sa.inv().map(|i| i.num), fa.inv().map(|i| i.num));
+            assert_eq!(sa.sqrt().map(|r| r.num), fa.sqrt().map(|r| r.num));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_field_fast_reduction_edge_cases() -> Result<(), Box<dyn std::error::Error>> {
+        let c = U256::from(0x1000003D1u64);
+        let edges = [
+            U256::zero(),
+            U256::one(),
+            c,
+            c - 1,
+            P - 1,
+            P - 2,
+            P - c,
+            U256::one() << 255,
+            (U256::one() << 128) - 1,
+            P >> 1,
+        ];
+
+        for &a in edges.iter() {
+            for &b in edges.iter() {
+                let product = (S256Field::new(a)? * S256Field::new(b)?).num;
+
+                assert!(product < P);
+                assert_eq!(product, a.mul_mod(b, P));
+            }
+            assert_eq!(S256Field::new(a)?.square(), S256Field::new(a)? * S256Field::new(a)?);
+        }
+
+        let mut state = 0x853C49E6748FEA9B;
+        for _ in 0..1000 {
+            let a = random_u256(&mut state, P);
+            let b = random_u256(&mut state, P);
+
+            assert_eq!((S256Field::new(a)? * S256Field::new(b)?).num, a.mul_mod(b, P));
+            assert_eq!(S256Field::new(a)?.square().num, a.mul_mod(a, P));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_field_constant_time_helpers() -> Result<(), Box<dyn std::error::Error>> {
+        let a = S256Field::new(U256::from(5))?;
+        let b = S256Field::new(P - U256::from(3))?;
+
+        assert_eq!(S256Field::conditional_select(&a, &b, false), a);
+        assert_eq!(S256Field::conditional_select(&a, &b, true), b);
+
+        let (mut c, mut d) = (a, b);
+        S256Field::conditional_swap(&mut c, &mut d, false);
+        assert_eq!((c, d), (a, b));
+        S256Field::conditional_swap(&mut c, &mut d, true);
+        assert_eq!((c, d), (b, a));
+
+        assert!(a.ct_eq(&a));
+        assert!(!a.ct_eq(&b));
+
+        // 分岐しない加減算でも正規化される
+        assert_eq!(b + b, S256Field::new(P - U256::from(6))?);
+        assert_eq!(a - b, S256Field::new(U256::from(8))?);
+        assert_eq!(b - a, S256Field::new(P - U256::from(8))?);
+        assert_eq!(-S256Field::ZERO, S256Field::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_point_mul_ct() -> Result<(), Box<dyn std::error::Error>> {
+        let gx = s256_hex("79BE667EF9DCBBAC55A06295CE870B07029BFCDB2DCE28D959F2815B16F81798");
+        let gy = s256_hex("483ADA7726A3C4655DA4FBFC0E1108A8FD17B448A68554199C47D08FFB10D4B8");
+        let g = S256Point::new(gx, gy, &secp256k1::curve())?;
+        let infinity = S256Point::infinity(&secp256k1::curve());
+
+        assert_eq!(g.mul_ct(U256::zero()), infinity);
+        assert_eq!(g.mul_ct(U256::one()), g);
+        assert_eq!(g.mul_ct(N), infinity);
+        assert_eq!(g.mul_ct(N - U256::one()), (&g * (N - U256::one()))?);
+        assert_eq!(infinity.mul_ct(U256::from(12345)), infinity);
+
+        let mut state = 0xA0761D6478BD642F;
+        for _ in 0..5 {
+            let secret = random_u256(&mut state, N);
+            assert_eq!(g.mul_ct(secret), (&g * secret)?);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn batch_invert_field_elements() -> Result<(), Box<dyn std::error::Error>> {
+        let elems = (1..31).map(|n| FieldElement::new(n, 31)).collect::<Result<Vec<_>, _>>()?;
+        let inverses = batch_invert(&elems)?;
+
+        for (e, inv) in elems.iter().zip(inverses.iter()) {
+            assert_eq!(Some(*inv), e.inv());
+        }
+        assert!(batch_invert::<FieldElement<i32>>(&[])?.is_empty());
+
+        let with_zero = [FieldElement::new(3, 31)?, FieldElement::new(5, 31)?, FieldElement::new(0, 31)?];
+        assert!(matches!(batch_invert(&with_zero), Err(ExpressionError::ZeroDivisionAt(2))));
+
+        let mixed = [FieldElement::new(3, 31)?, FieldElement::new(5, 37)?];
+        assert!(matches!(batch_invert(&mixed), Err(ExpressionError::DifferentOrderExpression)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn batch_invert_s256_field() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0xE7037ED1A0B428DB;
+        let elems: Vec<S256Field> = (0..50).map(|_| S256Field::new(random_u256(&mut state, P - 1) + 1).unwrap()).collect();
+        let inverses = batch_invert(&elems)?;
+
+        for (e, inv) in elems.iter().zip(inverses.iter()) {
+            assert_eq!(*e * *inv, S256Field::ONE);
+        }
+
+        let mut with_zero = elems.clone();
+        with_zero[17] = S256Field::ZERO;
+        assert!(matches!(batch_invert(&with_zero), Err(ExpressionError::ZeroDivisionAt(17))));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ext_gcd_and_inv_mod() {
+        let (g, t) = ext_gcd(U256::from(240), U256::from(46));
+        assert_eq!(g, U256::from(2));
+        assert_eq!(U256::from(240) * t % 46, g);
+        assert_eq!(inv_mod(U256::from(3), U256::from(31)), Some(U256::from(21)));
+        assert_eq!(inv_mod(U256::from(6), U256::from(15)), None);
+
+        let mut state = 0x2545F4914F6CDD1D;
+        for _ in 0..20 {
+            let a = random_u256(&mut state, N - 1) + 1;
+            let inv = inv_mod(a, N).unwrap();
+            assert_eq!(a.full_mul(inv) % U512::from(N), U512::one());
+        }
+    }
+
+    #[test]
+    fn s256_inversion_methods_agree() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0x9E3779B97F4A7C15;
+        let mut elems = vec![S256Field::ONE, S256Field::new(P - 1)?, S256Field::new(U256::from(2))?];
+        elems.extend((0..20).map(|_| S256Field::new(random_u256(&mut state, P - 1) + 1).unwrap()));
+
+        for e in elems {
+            let fermat = e.inv_with(Inversion::Fermat).unwrap();
+            assert_eq!(e * fermat, S256Field::ONE);
+            assert_eq!(e.inv_with(Inversion::Euclid), Some(fermat));
+            assert_eq!(e.inv_with(Inversion::SafeGcd), Some(fermat));
+        }
+
+        for method in [Inversion::Fermat, Inversion::Euclid, Inversion::SafeGcd] {
+            assert_eq!(S256Field::ZERO.inv_with(method), None);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn miller_rabin() {
+        let primes: Vec<u64> = (0..200).filter(|&n| is_prime(n)).collect();
+        assert_eq!(primes.len(), 46);
+        assert_eq!(&primes[..6], &[2, 3, 5, 7, 11, 13]);
+        assert_eq!(primes.last(), Some(&199));
+
+        // カーマイケル数や強擬素数
+        for n in [561u64, 1105, 1729, 2047, 3215031751, 3825123056546413051] {
+            assert!(!is_prime(n), "{}", n);
+        }
+        assert!(is_prime((1u64 << 61) - 1));
+        assert!(is_prime(u64::MAX - 58));
+        assert!(!is_prime(-7i32));
+
+        assert!(is_prime((1u128 << 127) - 1));
+        assert!(!is_prime(((1u128 << 61) - 1) * ((1u128 << 61) - 1)));
+        assert!(is_prime(P));
+        assert!(is_prime(N));
+        assert!(!is_prime(U256::from((1u128 << 127) - 1) * U256::from(u64::MAX - 58)));
+    }
+
+    #[test]
+    fn field_element_rejects_composite_modulus() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(matches!(FieldElement::new(2, 15), Err(ExpressionError::CompositeModulus)));
+        assert!(matches!(FieldElement::new(2, 1), Err(ExpressionError::CompositeModulus)));
+        assert!(matches!(FieldElement::new(15, 13), Err(ExpressionError::InvalidFieldElement)));
+
+        // 合成数を法とする環の演算は ZnElement で行う
+        // 指数は還元されないので 2^15 mod 15 = 8
+        let a = ZnElement::new(7u64, 15)?;
+        let b = ZnElement::new(11u64, 15)?;
+        assert_eq!((a * b)?, ZnElement::new(2, 15)?);
+        assert_eq!((a + b)?, ZnElement::new(3, 15)?);
+        assert_eq!(ZnElement::new(2u64, 15)?.pow(15u32), ZnElement::new(8, 15)?);
+        assert!(matches!(FieldElement::new(8u64, 9), Err(ExpressionError::CompositeModulus)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn zn_element_arithmetic() -> Result<(), Box<dyn std::error::Error>> {
+        let zn = |n: u64| ZnElement::new(n, 91u64);
+
+        assert_eq!((zn(50)? + zn(60)?)?, zn(19)?);
+        assert_eq!((zn(10)? - zn(20)?)?, zn(81)?);
+        assert_eq!((zn(12)? * zn(15)?)?, zn(89)?);
+        assert_eq!((zn(1)? / zn(2)?)?, zn(46)?);
+        assert_eq!(zn(3)?.pow(-1), zn(61)?);
+        assert_eq!(zn(3)?.pow(12), zn(3u64.pow(12) % 91)?);
+        assert_eq!(zn(3)?.pow(-2), zn(61 * 61 % 91)?);
+        // 逆元が存在しない元の負の冪は0
+        assert_eq!(zn(14)?.pow(-1), zn(0)?);
+
+        // 91 = 7 * 13
+        assert!(matches!(zn(1)? / zn(14)?, Err(ExpressionError::NonInvertible(d)) if d == U256::from(7)));
+        assert!(matches!(zn(26)?.try_inv(), Err(ExpressionError::NonInvertible(d)) if d == U256::from(13)));
+        assert!(matches!(zn(1)? / zn(0)?, Err(ExpressionError::ZeroDivision)));
+        assert!(matches!(zn(1)? + ZnElement::new(1, 15)?, Err(ExpressionError::DifferentOrderExpression)));
+        assert!(matches!(ZnElement::new(91u64, 91), Err(ExpressionError::InvalidFieldElement)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_over_zn_reports_factor() -> Result<(), Box<dyn std::error::Error>> {
+        // y^2 = x^3 + x + 12 (mod 35) 上の (1, 7)
+        // 接線の傾きの分母 2y = 14 が 35 と共通の約数7を持つ
+        let zn = |n: u64| ZnElement::new(n, 35u64);
+        let curve = Arc::new(Curve::new("y^2 = x^3 + x + 12 mod 35", zn(1)?, zn(12)?)?);
+        let p = Point::new(zn(1)?, zn(7)?, &curve)?;
+
+        assert!(matches!(&p + &p, Err(ExpressionError::NonInvertible(d)) if d == U256::from(7)));
+        assert!(matches!(&p * 2, Err(ExpressionError::NonInvertible(d)) if d == U256::from(7)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ecm_factors_semiprimes() {
+        let mut rng = XorShift64::new(1);
+
+        // 40bit, 60bit
+        for (p, q) in [(1000003u64, 1000033u64), (1073741827, 1073741831)] {
+            let d = ecm(p * q, 2000, 200, &mut rng).unwrap();
+            assert!(d == p || d == q, "{}", d);
+        }
+
+        assert_eq!(ecm(2u64 * 1000003, 2000, 1, &mut rng), Some(2));
+        assert_eq!(ecm(1000003u64, 2000, 10, &mut rng), None);
+        assert_eq!(ecm(1u64, 2000, 10, &mut rng), None);
+    }
+
+    #[test]
+    fn polynomial_arithmetic() -> Result<(), Box<dyn std::error::Error>> {
+        type F = Fp<97>;
+        let poly = |cs: &[u64]| -> Result<Polynomial<F>, ExpressionError> {
+            Ok(Polynomial::new(cs.iter().map(|&c| F::new(c)).collect::<Result<Vec<_>, _>>()?))
+        };
+
+        // (x + 1)(x - 1) = x^2 - 1
+        let a = poly(&[1, 1])?;
+        let b = poly(&[96, 1])?;
+        assert_eq!((&a * &b)?, poly(&[96, 0, 1])?);
+        assert_eq!((&a + &b)?, poly(&[0, 2])?);
+        assert_eq!((&a - &a)?, Polynomial::zero());
+        assert_eq!(poly(&[3, 0, 0])?.degree(), Some(0));
+        assert_eq!(Polynomial::<F>::zero().degree(), None);
+
+        // 3x^3 + 2x + 5 at x = 4
+        let f = poly(&[5, 2, 0, 3])?;
+        assert_eq!(f.eval(F::new(4)?)?, F::new((3 * 64 + 8 + 5) % 97)?);
+        assert_eq!(f.derivative()?, poly(&[2, 0, 9])?);
+
+        let g = poly(&[7, 0, 5])?;
+        let (q, r) = f.divrem(&g)?;
+        assert!(r.degree() < g.degree());
+        assert_eq!((&(&q * &g)? + &r)?, f);
+        assert!(matches!(f.divrem(&Polynomial::zero()), Err(ExpressionError::ZeroDivision)));
+
+        // gcd((x-1)(x-2), 5(x-1)(x-3)) = x - 1
+        let x1 = Polynomial::linear_root(F::new(1)?);
+        let p = (&x1 * &Polynomial::linear_root(F::new(2)?))?;
+        let q = (&x1 * &Polynomial::linear_root(F::new(3)?))?.scale(F::new(5)?)?;
+        assert_eq!(p.gcd(&q)?, x1);
+
+        Ok(())
+    }
+
+    #[test]
+    fn lagrange_interpolation() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0xA0761D6478BD642F;
+        let f = Polynomial::new((0..5).map(|_| S256Field::new(random_u256(&mut state, P)).unwrap()).collect());
+
+        let points = (1..=5u64)
+            .map(|x| {
+                let x = S256Field::new(U256::from(x))?;
+                Ok((x, f.eval(x)?))
+            })
+            .collect::<Result<Vec<_>, ExpressionError>>()?;
+        assert_eq!(Polynomial::interpolate(&points)?, f);
+        assert_eq!(Polynomial::interpolate(&points[..1])?, Polynomial::constant(points[0].1));
+        assert_eq!(Polynomial::<S256Field>::interpolate(&[])?, Polynomial::zero());
+
+        let duplicated = [points[0], points[1], points[0]];
+        assert!(matches!(Polynomial::interpolate(&duplicated), Err(ExpressionError::ZeroDivision)));
+
+        // FieldElementの場合、異なる体の点はErr
+        let a = (FieldElement::new(1, 31)?, FieldElement::new(2, 31)?);
+        let b = (FieldElement::new(3, 37)?, FieldElement::new(4, 37)?);
+        assert!(matches!(Polynomial::interpolate(&[a, b]), Err(ExpressionError::DifferentOrderExpression)));
+
+        Ok(())
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn sha256_test_vectors() {
+        assert_eq!(to_hex(&sha256(b"")), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(to_hex(&sha256(b"abc")), "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad");
+        assert_eq!(
+            to_hex(&sha256(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+        assert_eq!(to_hex(&sha256(&[b'a'; 1000])), "41edece42d63e8d9bf515a9ba6932e1c20cbc9f5a5d134645adb5db1b9737ea3");
+        assert_eq!(to_hex(&hash256(b"hello")), "9595c9df90075148eb06860365df33584b75bff782a510c6cd4883a419833d50");
+    }
+
+    // テスト用の決定的な乱数
+    fn test_fill(state: &mut u64) -> impl FnMut(&mut [u8; 32]) -> Result<(), ExpressionError> + '_ {
+        move |buf| {
+            let value = random_u256(state, U256::MAX);
+            buf.copy_from_slice(&value.to_big_endian());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn shamir_split_and_recover() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0x5851F42D4C957F2D;
+        let secret = (N - 12345).to_big_endian();
+        let shares = shamir::split_with(&secret, 3, 5, test_fill(&mut state))?;
+        assert_eq!(shares.len(), 5);
+
+        // どの3つの組み合わせからも復元できる
+        for i in 0..5 {
+            for j in i + 1..5 {
+                for k in j + 1..5 {
+                    assert_eq!(shamir::recover(&[shares[i], shares[j], shares[k]], 3)?, secret);
+                }
+            }
+        }
+        assert_eq!(shamir::recover(&shares, 3)?, secret);
+        assert!(matches!(shamir::recover(&shares[..2], 3), Err(ExpressionError::NotEnoughShares)));
+        assert!(matches!(shamir::recover(&[shares[0], shares[0], shares[1]], 3), Err(ExpressionError::NotEnoughShares)));
+
+        // OSの乱数でも同じように使える
+        let shares = shamir::split(&secret, 2, 3)?;
+        assert_eq!(shamir::recover(&shares[1..], 2)?, secret);
+
+        // 1-of-1 はシェアが秘密そのもの
+        let shares = shamir::split(&secret, 1, 1)?;
+        assert_eq!(shares[0].value.to_big_endian(), secret);
+
+        assert!(matches!(shamir::split(&secret, 0, 3), Err(ExpressionError::InvalidThreshold)));
+        assert!(matches!(shamir::split(&secret, 4, 3), Err(ExpressionError::InvalidThreshold)));
+        assert!(matches!(shamir::split(&[0; 32], 2, 3), Err(ExpressionError::InvalidFieldElement)));
+        assert!(matches!(shamir::split(&N.to_big_endian(), 2, 3), Err(ExpressionError::InvalidFieldElement)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shamir_share_serialization() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0x14057B7EF767814F;
+        let secret = U256::from(0xDEADBEEFu64).to_big_endian();
+        let shares = shamir::split_with(&secret, 2, 3, test_fill(&mut state))?;
+
+        for share in &shares {
+            let bytes = share.serialize();
+            assert_eq!(bytes.len(), SHARE_LEN);
+            assert_eq!(bytes[0], share.index);
+            assert_eq!(Share::parse(&bytes)?, *share);
+
+            // 1ビットでも壊れていればチェックサムで検出する
+            for i in 0..SHARE_LEN {
+                let mut corrupted = bytes;
+                corrupted[i] ^= 0x01;
+                assert!(matches!(Share::parse(&corrupted), Err(ExpressionError::InvalidShare)));
+            }
+            assert!(matches!(Share::parse(&bytes[..36]), Err(ExpressionError::InvalidShare)));
+        }
+
+        // チェックサムが正しくても index 0 や N 以上の値は受け付けない
+        assert!(matches!(Share::parse(&Share { index: 0, value: U256::one() }.serialize()), Err(ExpressionError::InvalidShare)));
+        assert!(matches!(Share::parse(&Share { index: 1, value: N }.serialize()), Err(ExpressionError::InvalidShare)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn shamir_detects_inconsistent_shares() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0x2127599BF4325C37;
+        let secret = U256::from(42).to_big_endian();
+        let mut shares = shamir::split_with(&secret, 3, 5, test_fill(&mut state))?;
+
+        shares[4].value = (shares[4].value + 1) % N;
+        assert!(matches!(shamir::recover(&shares, 3), Err(ExpressionError::InconsistentShares)));
+        assert!(matches!(shamir::recover(&shares[1..], 3), Err(ExpressionError::InconsistentShares)));
+        // 壊れたシェアを除けば復元できる
+        assert_eq!(shamir::recover(&shares[..4], 3)?, secret);
+
+        // 同じindexで値が異なる
+        let conflicting = Share { index: shares[0].index, value: shares[1].value };
+        assert!(matches!(shamir::recover(&[shares[0], conflicting, shares[2], shares[3]], 3), Err(ExpressionError::InconsistentShares)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn feldman_shares_verify_against_commitments() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0x3C6EF372FE94F82B;
+        let secret = U256::from(0xC0FFEEu64);
+        let dealing = feldman::deal_with(&secret.to_big_endian(), 3, 5, test_fill(&mut state))?;
+
+        assert_eq!(dealing.commitments.len(), 3);
+        // C_0 は秘密鍵に対応する公開鍵
+        assert_eq!(dealing.commitments[0], (&secp256k1::generator() * secret)?);
+
+        for share in &dealing.shares {
+            assert!(feldman::verify_share(share, &dealing.commitments));
+
+            let mut tampered = *share;
+            tampered.value = (tampered.value + 1) % N;
+            assert!(!feldman::verify_share(&tampered, &dealing.commitments));
+        }
+        assert_eq!(feldman::recover(&dealing.shares[2..], &dealing.commitments)?, secret.to_big_endian());
+
+        let dealing = feldman::deal(&secret.to_big_endian(), 2, 3)?;
+        assert!(dealing.shares.iter().all(|share| feldman::verify_share(share, &dealing.commitments)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn feldman_detects_dishonest_dealer() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0xBB67AE8584CAA73B;
+        let secret = (N - 1).to_big_endian();
+        let mut dealing = feldman::deal_with(&secret, 2, 4, test_fill(&mut state))?;
+
+        // ディーラーが1人に多項式に乗らないシェアを配った
+        dealing.shares[1].value = (dealing.shares[1].value + 7) % N;
+        assert!(!feldman::verify_share(&dealing.shares[1], &dealing.commitments));
+        assert!(feldman::verify_share(&dealing.shares[0], &dealing.commitments));
+        assert!(matches!(feldman::recover(&dealing.shares, &dealing.commitments), Err(ExpressionError::InvalidShare)));
+        assert_eq!(feldman::recover(&[dealing.shares[0], dealing.shares[3]], &dealing.commitments)?, secret);
+
+        // コミットメントを差し替えると、正しいシェアも検証に通らない
+        dealing.commitments[1] = secp256k1::generator();
+        assert!(!feldman::verify_share(&dealing.shares[0], &dealing.commitments));
+        assert!(matches!(feldman::recover(&dealing.shares[..2], &[]), Err(ExpressionError::InvalidThreshold)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fp2_arithmetic() -> Result<(), Box<dyn std::error::Error>> {
+        // F_7[i] / (i^2 + 1) (7 = 3 mod 4 なので -1 は平方非剰余)
+        type F = Fp<7>;
+        let m = [F::ONE, F::ZERO];
+        let fp2 = |a: u64, b: u64| -> Result<Fp2<F>, ExpressionError> { Fp2::new([F::new(a)?, F::new(b)?], m) };
+
+        let i = fp2(0, 1)?;
+        assert_eq!(i.square(), fp2(6, 0)?);
+        // (3 + 4i)(5 + 6i) = -9 + 38i
+        assert_eq!((fp2(3, 4)? * fp2(5, 6)?)?, fp2(5, 3)?);
+        assert_eq!((fp2(3, 4)? + fp2(5, 6)?)?, fp2(1, 3)?);
+        assert_eq!((fp2(3, 4)? - fp2(5, 6)?)?, fp2(5, 5)?);
+
+        // 48個の非零元すべてに逆元があり、a^48 = 1
+        for a in 0..7 {
+            for b in 0..7 {
+                let x = fp2(a, b)?;
+                if x.is_zero() {
+                    assert_eq!(x.inv(), None);
+                    continue;
+                }
+                assert_eq!((x * x.inv().unwrap())?, x.one());
+                assert_eq!((x.one() / x)?, x.pow(-1));
+                assert_eq!(x.pow(48), x.one());
+                // フロベニウス写像 x^7 は共役 a - bi
+                assert_eq!(x.pow(7), fp2(a, (7 - b) % 7)?);
+            }
+        }
+
+        // i^2 - 1 = (i - 1)(i + 1) は可約なので逆元が存在しない元がある
+        let reducible = [F::new(6)?, F::ZERO];
+        let x = Fp2::new([F::ONE, F::ONE], reducible)?;
+        assert_eq!(x.inv(), None);
+
+        assert!(matches!(fp2(1, 0)? + Fp2::new([F::ONE, F::ONE], reducible)?, Err(ExpressionError::DifferentOrderExpression)));
+        let mixed = Fp2::new([FieldElement::new(1, 7)?, FieldElement::new(1, 11)?], [FieldElement::new(1, 7)?, FieldElement::new(0, 7)?]);
+        assert!(matches!(mixed, Err(ExpressionError::DifferentOrderExpression)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fp3_arithmetic() -> Result<(), Box<dyn std::error::Error>> {
+        // F_7[x] / (x^3 - 2) (2は F_7 で3乗数でない)
+        type F = Fp<7>;
+        let m = [F::new(5)?, F::ZERO, F::ZERO];
+
+        let mut count = 0;
+        for n in 1..343u64 {
+            let x = FpK::new([F::new(n % 7)?, F::new(n / 7 % 7)?, F::new(n / 49)?], m)?;
+            assert_eq!((x * x.inv().unwrap())?, x.one());
+            assert_eq!(x.pow(342), x.one());
+            count += 1;
+        }
+        assert_eq!(count, 342);
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_over_fp2() -> Result<(), Box<dyn std::error::Error>> {
+        // y^2 = x^3 + 7 over F_223^2 = F_223[i] / (i^2 + 1)
+        type F = Fp<223>;
+        let m = [F::ONE, F::ZERO];
+        let embed = |c: F| Fp2::from_base(c, m);
+        let curve = Arc::new(Curve::new("y^2 = x^3 + 7 over F_223^2", embed(F::ZERO)?, embed(F::new(7)?)?)?);
+
+        // x^3 + 7 が平方非剰余になる x では、y は F_223 に無く i の倍数になる
+        let x = (0..223).map(F::new).collect::<Result<Vec<_>, _>>()?
+            .into_iter()
+            .find(|x| !(x.square() * *x + F::new(7).unwrap()).is_quadratic_residue())
+            .unwrap();
+        let s = (-(x.square() * x + F::new(7)?)).sqrt().unwrap();
+        let p = Point::new(embed(x)?, Fp2::new([F::ZERO, s], m)?, &curve)?;
+
+        // F_223 上の点 (15, 86) との和も曲線上にある
+        let g = Point::new(embed(F::new(15)?)?, embed(F::new(86)?)?, &curve)?;
+        let sum = (&p + &g)?;
+        assert_eq!((&sum + &g)?, (&p + &(&g + &g)?)?);
+        assert_eq!((&p * 2)?, (&p + &p)?);
+        assert_eq!((&g * 7)?, Point::infinity(&curve));
+
+        // y座標を入れ替えると曲線から外れる
+        assert!(matches!(Point::new(embed(x)?, Fp2::new([s, F::ZERO], m)?, &curve), Err(ExpressionError::InvalidPoint)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn curve_registry_builtin_curves() -> Result<(), Box<dyn std::error::Error>> {
+        let registry = CurveRegistry::new();
+        assert_eq!(registry.names().collect::<Vec<_>>(), vec!["book-f223", "secp256k1"]);
+
+        let secp = registry.curve::<S256Field>("secp256k1")?;
+        assert_eq!(secp.p(), P);
+        assert_eq!(secp.n(), Some(N));
+        assert_eq!(secp.h(), Some(U256::one()));
+        let g = Point::generator(&secp).unwrap();
+        let base = secp.generator.unwrap();
+        assert_eq!(g, Point::new(base.x, base.y, &secp)?);
+        assert_eq!(secp256k1::generator().curve().generator, secp.generator);
+
+        // 同じパラメータを別の体の表現で読み込める
+        let toy = registry.curve::<Fp<223>>("book-f223")?;
+        let g = Point::generator(&toy).unwrap();
+        assert_eq!((&g * 7)?, Point::infinity(&toy));
+        assert_eq!(toy.n(), Some(U256::from(7)));
+        assert_eq!(toy.h(), Some(U256::from(36)));
+
+        let toy = registry.curve::<FieldElement<i64>>("book-f223")?;
+        let g = Point::generator(&toy).unwrap();
+        assert_eq!((&g * 7)?, Point::infinity(&toy));
+        // 点は曲線を共有する
+        assert!(Arc::ptr_eq((&g + &g)?.curve(), &toy));
+
+        assert!(matches!(registry.curve::<Fp<13>>("book-f223"), Err(ExpressionError::DifferentOrderExpression)));
+        assert!(matches!(registry.curve::<Fp<223>>("secp256k1"), Err(ExpressionError::DifferentOrderExpression)));
+        assert!(matches!(registry.curve::<Fp<223>>("P-256"), Err(ExpressionError::UnknownCurve)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn curve_registry_user_defined() -> Result<(), Box<dyn std::error::Error>> {
+        let mut registry = CurveRegistry::default();
+        let params = CurveParams {
+            name: "f97".to_string(),
+            p: U256::from(97),
+            a: U256::from(2),
+            b: U256::from(3),
+            gx: U256::from(3),
+            gy: U256::from(6),
+            n: U256::from(5),
+            h: U256::from(20),
+        };
+        assert_eq!(registry.register(params.clone())?, None);
+        assert_eq!(registry.get("f97"), Some(&params));
+
+        let curve = registry.curve::<Fp<97>>("f97")?;
+        let g = Point::generator(&curve).unwrap();
+        assert_eq!((&g * 5)?, Point::infinity(&curve));
+
+        // 別の曲線の点同士は足せない
+        let toy = registry.curve::<FieldElement<i64>>("book-f223")?;
+        let other = registry.curve::<FieldElement<i64>>("f97")?;
+        let g97 = Point::generator(&other).unwrap();
+        assert!(matches!(&Point::generator(&toy).unwrap() + &g97, Err(ExpressionError::DifferentCurves)));
+
+        // 不正なパラメータは登録できない
+        let composite = CurveParams { p: U256::from(91), ..params.clone() };
+        assert!(matches!(registry.register(composite), Err(ExpressionError::CompositeModulus)));
+        let off_curve = CurveParams { gy: U256::from(7), ..params.clone() };
+        assert!(matches!(registry.register(off_curve), Err(ExpressionError::InvalidPoint)));
+        let singular = CurveParams { a: U256::zero(), b: U256::zero(), gx: U256::zero(), gy: U256::zero(), ..params.clone() };
+        assert!(matches!(registry.register(singular), Err(ExpressionError::SingularCurve)));
+        // 4・2^3 + 27・3^2 = 275 = 81 (mod 97)
+        let f97 = |n: u64| FieldElement::new(U256::from(n), U256::from(97));
+        assert_eq!(Curve::discriminant(f97(2)?, f97(3)?)?, f97(81)?);
+        assert!(Curve::discriminant(f97(0)?, f97(0)?)?.is_zero());
+        let out_of_range = CurveParams { gx: U256::from(100), ..params.clone() };
+        assert!(matches!(registry.register(out_of_range), Err(ExpressionError::InvalidFieldElement)));
+        // nG が無限遠点にならない位数や、0の位数・補因子
+        let wrong_order = CurveParams { n: U256::from(7), ..params.clone() };
+        assert!(matches!(registry.register(wrong_order), Err(ExpressionError::InvalidGeneratorOrder)));
+        let zero_order = CurveParams { n: U256::zero(), ..params.clone() };
+        assert!(matches!(registry.register(zero_order), Err(ExpressionError::InvalidGeneratorOrder)));
+        let zero_cofactor = CurveParams { h: U256::zero(), ..params.clone() };
+        assert!(matches!(registry.register(zero_cofactor), Err(ExpressionError::InvalidGeneratorOrder)));
+        assert_eq!(registry.get("f97"), Some(&params));
+
+        // 同じ名前で登録すると置き換わる
+        let replaced = CurveParams { gx: U256::from(3), gy: U256::from(91), ..params.clone() };
+        assert_eq!(registry.register(replaced)?, Some(params));
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_point_matches_generic_field_element_point() -> Result<(), Box<dyn std::error::Error>> {
+        // 同じ Point の実装を S256Field と FieldElement<U256> で使っても結果が一致する
+        let generic = CurveRegistry::new().curve::<FieldElement<U256>>("secp256k1")?;
+        let g = Point::generator(&generic).unwrap();
+        let s = secp256k1::generator();
+
+        for k in [U256::from(2), U256::from(0xC0FFEEu64), N - U256::one()] {
+            let lhs = (&g * k)?;
+            let rhs = (&s * k)?;
+            assert_eq!(lhs.x().map(|x| x.num), rhs.x().map(|x| x.num));
+            assert_eq!(lhs.y().map(|y| y.num), rhs.y().map(|y| y.num));
+        }
+        assert_eq!((&s * N)?, S256Point::infinity(s.curve()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_infinity_is_explicit() -> Result<(), Box<dyn std::error::Error>> {
+        type F223 = Fp<223>;
+        let curve = Arc::new(Curve::new("book-f223", F223::ZERO, F223::new(7)?)?);
+        let g = Point::new(F223::new(15)?, F223::new(86)?, &curve)?;
+        let infinity = Point::infinity(&curve);
+
+        assert!(infinity.is_infinity());
+        assert!(!g.is_infinity());
+        assert_eq!(infinity.coordinates(), Coordinates::Infinity);
+        assert_eq!((infinity.x(), infinity.y()), (None, None));
+        assert_eq!(g.coordinates(), Coordinates::Affine { x: F223::new(15)?, y: F223::new(86)? });
+        assert_eq!((&g * 7)?.coordinates(), Coordinates::Infinity);
+
+        assert_eq!(Point::from_coordinates(g.coordinates(), &curve)?, g);
+        assert_eq!(Point::from_coordinates(Coordinates::Infinity, &curve)?, infinity);
+        let off_curve = Coordinates::Affine { x: F223::new(15)?, y: F223::new(87)? };
+        assert!(matches!(Point::from_coordinates(off_curve, &curve), Err(ExpressionError::InvalidPoint)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn s256_point_sec_serialization() -> Result<(), Box<dyn std::error::Error>> {
+        let hex = |s: &str| (0..s.len()).step_by(2).map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap()).collect::<Vec<_>>();
+        let g = secp256k1::generator();
+
+        let p = (&g * 5000u32)?;
+        let sec = hex("04ffe558e388852f0120e46af2d1b370f85854a8eb0841811ece0e3e03d282d57c315dc72890a4f10a1481c031b03b351b0dc79901ca18a00cf009dbdb157a1d10");
+        assert_eq!(p.serialize(false), sec);
+        assert_eq!(S256Point::parse(&sec)?, p);
+
+        let p = (&g * 5001u32)?;
+        let sec = hex("0357a4f368868a8a6d572991e484e664810ff14c05c0fa023275251151fe0e53d1");
+        assert_eq!(p.serialize(true), sec);
+        assert_eq!(S256Point::parse(&sec)?, p);
+        assert_eq!(S256Point::parse(&p.serialize(false))?, p);
+
+        // 偶数のyも圧縮形式から復元できる
+        let neg = (&g * (N - U256::from(5001)))?;
+        assert_eq!(neg.serialize(true)[0], 0x02);
+        assert_eq!(S256Point::parse(&neg.serialize(true))?, neg);
+
+        // 無限遠点は0x00の1バイトだけ
+        let infinity = S256Point::infinity(g.curve());
+        assert_eq!(infinity.serialize(true), vec![0x00]);
+        assert_eq!(infinity.serialize(false), vec![0x00]);
+        assert!(S256Point::parse(&[0x00])?.is_infinity());
+
+        for bad in [vec![], vec![0x00, 0x00], vec![0x05; 33], sec[..32].to_vec(), vec![0x04; 65]] {
+            assert!(matches!(S256Point::parse(&bad), Err(ExpressionError::InvalidPoint)));
+        }
+        // x >= p
+        let mut too_big = vec![0x02];
+        too_big.extend_from_slice(&[0xFF; 32]);
+        assert!(matches!(S256Point::parse(&too_big), Err(ExpressionError::InvalidPoint)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn jacobian_point_matches_affine_addition() -> Result<(), Box<dyn std::error::Error>> {
+        // a != 0 の曲線 y^2 = x^3 + 2x + 3 over F_97 (点の数は100)
+        type F97 = Fp<97>;
+        let curve = Arc::new(Curve::new("f97", F97::new(2)?, F97::new(3)?)?);
+        let p = Point::new(F97::new(3)?, F97::new(6)?, &curve)?;
+        let infinity = Point::infinity(&curve);
+
+        // kP をアフィン座標の加算だけで求めたものと比べる
+        let mut affine = infinity.clone();
+        let mut jacobian = JacobianPoint::from_affine(&infinity);
+        for k in 1..=100u32 {
+            affine = (&affine + &p)?;
+            jacobian = jacobian.add_affine(&p)?;
+            assert_eq!(jacobian.to_affine()?, affine);
+            assert_eq!(JacobianPoint::from(&affine).double()?.to_affine()?, (&affine + &affine)?);
+            assert_eq!((&p * k)?, affine);
+        }
+        // 位数は点の数100を割り切る
+        assert!(jacobian.is_infinity());
+
+        // 逆元同士の和と、無限遠点との和
+        let neg = (&p * 99)?;
+        assert!(JacobianPoint::from(&p).add_affine(&neg)?.is_infinity());
+        assert_eq!(JacobianPoint::from(&p).add_affine(&infinity)?.to_affine()?, p);
+        assert!(JacobianPoint::infinity(&curve).double()?.is_infinity());
+
+        let other = Arc::new(Curve::new("f97", F97::new(2)?, F97::new(4)?)?);
+        let q = Point::new(F97::new(0)?, F97::new(2)?, &other)?;
+        assert!(matches!(JacobianPoint::from(&p).add_affine(&q), Err(ExpressionError::DifferentCurves)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn point_neg_sub_double_and_sum() -> Result<(), Box<dyn std::error::Error>> {
+        type F223 = Fp<223>;
+        let curve = Arc::new(Curve::new("book-f223", F223::ZERO, F223::new(7)?)?);
+        let g = Point::new(F223::new(15)?, F223::new(86)?, &curve)?;
+        let infinity = Point::infinity(&curve);
+
+        assert_eq!(-&g, Point::new(F223::new(15)?, F223::new(223 - 86)?, &curve)?);
+        assert_eq!(-&g, (&g * 6)?);
+        assert_eq!(-infinity.clone(), infinity);
+        assert_eq!((&g - &g)?, infinity);
+        assert_eq!((&infinity - &g)?, -&g);
+        assert_eq!((&g - &infinity)?, g);
+        assert_eq!(((&g * 5)? - &(&g * 2)?)?, (&g * 3)?);
+
+        assert_eq!(g.double()?, (&g + &g)?);
+        assert_eq!(infinity.double()?, infinity);
+        assert_eq!((&g * 7)?.double()?, infinity);
+
+        let mut acc = infinity.clone();
+        acc += &g;
+        acc += (&g * 2)?;
+        assert_eq!(acc, (&g * 3)?);
+        acc -= &g;
+        assert_eq!(acc, g.double()?);
+
+        // 1G + 2G + ... + 7G = 28G = 0G
+        let multiples = (1..=7u32).map(|k| &g * k).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(Point::sum_on(&curve, &multiples)?, infinity);
+        assert_eq!(Point::try_sum(&multiples[..3])?, (&g * 6)?);
+        assert_eq!(Point::sum_on(&curve, &[])?, infinity);
+        assert_eq!(multiples.iter().sum::<Result<_, _>>()?, infinity);
+        assert_eq!(multiples[..3].iter().cloned().sum::<Result<_, _>>()?, (&g * 6)?);
+        assert!(matches!(Vec::<Point<F223>>::new().into_iter().sum::<Result<_, _>>(), Err(ExpressionError::EmptyIterator)));
+        assert_eq!(Point::try_sum(&multiples[1..2])?, multiples[1]);
+        assert!(matches!(Point::<F223>::try_sum(&[]), Err(ExpressionError::EmptyIterator)));
+
+        // y^2 = x^3 + 5 上の点 (3, ±sqrt(32))
+        let other = Arc::new(Curve::new("other", F223::ZERO, F223::new(5)?)?);
+        let x = F223::new(3)?;
+        let q = Point::new(x, (x.square() * x + F223::new(5)?).sqrt().unwrap(), &other)?;
+        assert!(matches!(&g - &q, Err(ExpressionError::DifferentCurves)));
+        assert!(matches!(Point::try_sum([&g, &q]), Err(ExpressionError::DifferentCurves)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn schnorr_style_verification_equation() -> Result<(), Box<dyn std::error::Error>> {
+        let g = secp256k1::generator();
+        let scalar = |n: U256| FieldElement::new(n, N);
+
+        // 公開鍵 P = xG、nonceの点 R = kG、署名 s = k + ex に対して sG - eP = R
+        let (x, k, e) = (scalar(U256::from(0xC0FFEEu64))?, scalar(N - U256::from(12345))?, scalar(U256::from(0xDEADBEEFu64))?);
+        let s = (k + (e * x)?)?;
+        let public = (&g * x.num)?;
+        let r = (&g * k.num)?;
+
+        assert_eq!(((&g * s.num)? - &(&public * e.num)?)?, r);
+        assert_eq!(S256Point::sum_on(g.curve(), &[(&g * s.num)?, -(&public * e.num)?])?, r);
+        assert!(S256Point::sum_on(g.curve(), &Vec::new())?.is_infinity());
+        assert!((&r - &r)?.is_infinity());
+
+        Ok(())
+    }
+
+    #[test]
+    fn zero_pow_multiple_of_group_order() -> Result<(), Box<dyn std::error::Error>> {
+        // 指数をp-1で還元しても 0^(k(p-1)) = 0 のまま
+        let zero = FieldElement::new(0i32, 13)?;
+        assert_eq!(zero.pow(12), zero);
+        assert_eq!(zero.pow(36u32), zero);
+        assert_eq!(zero.pow(0), FieldElement::new(1i32, 13)?);
+        assert_eq!(FieldElement::new(5i32, 13)?.pow(24), FieldElement::new(1i32, 13)?);
+
+        assert_eq!(Fp::<13>::ZERO.pow(12u32), Fp::<13>::ZERO);
+        assert_eq!(Fp::<13>::ZERO.pow(U256::from(120)), Fp::<13>::ZERO);
+
+        assert_eq!(S256Field::ZERO.pow(P - 1), S256Field::ZERO);
+        assert_eq!(S256Field::new(U256::from(2))?.pow(P - 1), S256Field::ONE);
+
+        Ok(())
+    }
+
+    #[test]
+    fn random_source_errors_are_returned() {
+        let secret = U256::from(0xC0FFEEu64).to_big_endian();
+        let unavailable = |_: &mut [u8; 32]| Err(ExpressionError::RandomUnavailable(std::io::ErrorKind::NotFound));
+
+        assert!(matches!(shamir::split_with(&secret, 2, 3, unavailable), Err(ExpressionError::RandomUnavailable(std::io::ErrorKind::NotFound))));
+        assert!(matches!(feldman::deal_with(&secret, 2, 3, unavailable), Err(ExpressionError::RandomUnavailable(_))));
+        // 係数が不要な 1-of-n では乱数を使わない
+        assert!(shamir::split_with(&secret, 1, 3, unavailable).is_ok());
+    }
+
+    #[test]
+    fn field_rem_is_zero_for_nonzero_divisor() -> Result<(), Box<dyn std::error::Error>> {
+        assert_eq!(S256Field::new(U256::from(5))? % S256Field::new(U256::from(3))?, S256Field::ZERO);
+        assert_eq!(Fp::<13>::new(5).unwrap() % Fp::<13>::new(3).unwrap(), Fp::<13>::ZERO);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot divide by zero")]
+    fn field_rem_by_zero_panics() {
+        let _ = S256Field::new(U256::from(5)).unwrap() % S256Field::ZERO;
+    }
+
+    #[test]
+    fn try_div_by_zero_is_an_error_for_every_field() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(matches!(S256Field::ONE.try_div(S256Field::ZERO), Err(ExpressionError::ZeroDivision)));
+        assert!(matches!(Fp::<13>::ONE.try_div(Fp::<13>::ZERO), Err(ExpressionError::ZeroDivision)));
+        assert!(matches!(FieldElement::new(1, 13)?.try_div(FieldElement::new(0, 13)?), Err(ExpressionError::ZeroDivision)));
+        assert_eq!(Fp::<13>::ONE.try_div(Fp::<13>::new(2)?)?, Fp::<13>::new(7)?);
+
+        Ok(())
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot divide by zero")]
+    fn s256_field_div_by_zero_panics() {
+        let _ = S256Field::ONE / S256Field::ZERO;
+    }
+
+    #[test]
+    fn s256_mont_field_matches_s256_field() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0x6A09E667F3BCC909;
+        assert_eq!(S256MontField::new(U256::one())?, S256MontField::ONE);
+        assert_eq!(S256MontField::ZERO.num(), U256::zero());
+        assert!(matches!(S256MontField::new(P), Err(ExpressionError::InvalidFieldElement)));
+
+        for _ in 0..200 {
+            let (a, b) = (random_u256(&mut state, P), random_u256(&mut state, P));
+            let (sa, sb) = (S256Field::new(a)?, S256Field::new(b)?);
+            let (ma, mb) = (S256MontField::new(a)?, S256MontField::new(b)?);
+
+            assert_eq!(ma.num(), a);
+            assert_eq!(S256Field::from(ma), sa);
+            assert_eq!(S256MontField::from(sa), ma);
+            assert_eq!((ma + mb).num(), (sa + sb).num);
+            assert_eq!((ma - mb).num(), (sa - sb).num);
+            assert_eq!((ma * mb).num(), (sa * sb).num);
+            assert_eq!(ma.square().num(), sa.square().num);
+            assert_eq!(ma.try_div(mb)?.num(), sa.try_div(sb)?.num);
+            assert_eq!(ma.pow(b).num(), sa.pow(b).num);
+            assert_eq!(ma.inv().map(|i| i.num()), sa.inv().map(|i| i.num));
+        }
+        assert!(matches!(S256MontField::ONE.try_div(S256MontField::ZERO), Err(ExpressionError::ZeroDivision)));
+
+        // どちらの実装でも同じ点になる
+        let params = CurveParams::secp256k1();
+        let mont_curve = Arc::new(Curve::<S256MontField>::from_params(&params)?);
+        let g = Point::generator(&mont_curve).unwrap();
+        let s = secp256k1::generator();
+        for k in [U256::from(2), U256::from(0xC0FFEEu64), N - U256::one()] {
+            let (lhs, rhs) = ((&g * k)?, (&s * k)?);
+            assert_eq!(lhs.x().map(|x| x.num()), rhs.x().map(|x| x.num));
+            assert_eq!(lhs.y().map(|y| y.num()), rhs.y().map(|y| y.num));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn const_prime_check_matches_miller_rabin() {
+        for n in 0..2000u64 {
+            assert_eq!(is_prime_u64(n), is_prime(n), "{}", n);
+        }
+        for n in [(1u64 << 61) - 1, u64::MAX - 58, 3215031751, ((1u64 << 31) - 1) * ((1u64 << 31) - 1), u64::MAX] {
+            assert_eq!(is_prime_u64(n), is_prime(n), "{}", n);
+        }
+
+        // Fp<P> の P はコンパイル時に確認される
+        const _: () = assert!(is_prime_u64(Fp::<223>::PRIME));
+        const _: () = assert!(!is_prime_u64(15) && !is_prime_u64(1));
+    }
+
+    #[test]
+    fn s256_scalar_matches_field_element() -> Result<(), Box<dyn std::error::Error>> {
+        let mut state = 0xBB67AE8584CAA73B;
+        assert_eq!(Montgomery::new(N), N_MONTGOMERY);
+        assert_eq!(S256Scalar::new(U256::one())?, S256Scalar::ONE);
+        assert_eq!(S256Scalar::ZERO.num(), U256::zero());
+        assert!(matches!(S256Scalar::new(N), Err(ExpressionError::InvalidFieldElement)));
+
+        for _ in 0..200 {
+            let (a, b) = (random_u256(&mut state, N), random_u256(&mut state, N));
+            let (fa, fb) = (FieldElement::new(a, N)?, FieldElement::new(b, N)?);
+            let (sa, sb) = (S256Scalar::new(a)?, S256Scalar::new(b)?);
+
+            assert_eq!(sa.num(), a);
+            assert_eq!(sa.try_add(sb)?.num(), (fa + fb)?.num);
+            assert_eq!(sa.try_sub(sb)?.num(), (fa - fb)?.num);
+            assert_eq!(sa.try_mul(sb)?.num(), (fa * fb)?.num);
+            assert_eq!(sa.neg().num(), fa.neg().num);
+            assert_eq!(sa.try_div(sb)?.num(), (fa / fb)?.num);
+        }
+        // N - 1 + 1 = 0 の桁上がり、0 - 1 = N - 1 の桁借り
+        let max = S256Scalar::new(N - U256::one())?;
+        assert!(max.try_add(S256Scalar::ONE)?.is_zero());
+        assert_eq!(S256Scalar::ZERO.try_sub(S256Scalar::ONE)?, max);
+        assert!(matches!(S256Scalar::ONE.try_div(S256Scalar::ZERO), Err(ExpressionError::ZeroDivision)));
+
+        Ok(())
+    }
 }
@@ -0,0 +1,112 @@
+use primitive_types::{U256, U512};
+use crate::ct;
+use crate::field_element::pow_by_squaring;
+
+// 256bitの奇数mを法とするモンゴメリ表現 (R = 2^256)
+// aのモンゴメリ表現は aR mod m で、乗算の度に%で割る代わりにRで割る (シフトする)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Montgomery {
+    pub modulus: U256,
+    // -m^(-1) mod 2^64
+    m_inv: u64,
+    // R mod m (1のモンゴメリ表現)
+    r: U256,
+    // R^2 mod m
+    r2: U256,
+}
+
+impl Montgomery {
+    pub fn new(modulus: U256) -> Self {
+        assert!(modulus.bit(0), "modulus must be odd");
+
+        // ニュートン法で m^(-1) mod 2^64 を求める (1回毎に正しいビット数が倍になる)
+        let m0 = modulus.low_u64();
+        let mut inv = 1u64;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(m0.wrapping_mul(inv)));
+        }
+
+        let r = U256::try_from((U512::one() << 256) % U512::from(modulus)).unwrap();
+        let r2 = U256::try_from(r.full_mul(r) % U512::from(modulus)).unwrap();
+
+        Montgomery { modulus, m_inv: inv.wrapping_neg(), r, r2 }
+    }
+
+    // 事前に計算したパラメータから作る (定数用)
+    pub const fn from_parts(modulus: U256, m_inv: u64, r: U256, r2: U256) -> Self {
+        Montgomery { modulus, m_inv, r, r2 }
+    }
+
+    // a -> aR mod m
+    pub fn to_montgomery(&self, a: U256) -> U256 {
+        self.mul(a, self.r2)
+    }
+
+    // aR -> a mod m
+    pub fn from_montgomery(&self, a: U256) -> U256 {
+        self.mul(a, U256::one())
+    }
+
+    pub fn one(&self) -> U256 {
+        self.r
+    }
+
+    // モンゴメリ乗算 abR^(-1) mod m (CIOS法)
+    pub fn mul(&self, a: U256, b: U256) -> U256 {
+        let n = self.modulus.0;
+        let mut t = [0u64; 6];
+
+        for i in 0..4 {
+            // t += a * b[i]
+            let mut carry = 0u128;
+            for (tj, &aj) in t.iter_mut().zip(a.0.iter()) {
+                let sum = *tj as u128 + aj as u128 * b.0[i] as u128 + carry;
+                *tj = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[4] as u128 + carry;
+            t[4] = sum as u64;
+            t[5] = (sum >> 64) as u64;
+
+            // t += m * n で下位64bitを0にしてから64bitシフトする
+            let m = t[0].wrapping_mul(self.m_inv);
+            let mut carry = (t[0] as u128 + m as u128 * n[0] as u128) >> 64;
+            for j in 1..4 {
+                let sum = t[j] as u128 + m as u128 * n[j] as u128 + carry;
+                t[j - 1] = sum as u64;
+                carry = sum >> 64;
+            }
+            let sum = t[4] as u128 + carry;
+            t[3] = sum as u64;
+            t[4] = t[5] + (sum >> 64) as u64;
+        }
+
+        // 秘密の値にも使うので、mを引くかどうかは分岐せずに選ぶ
+        let res = U256([t[0], t[1], t[2], t[3]]);
+        let (diff, borrow) = res.overflowing_sub(self.modulus);
+        ct::select_u256(res, diff, (t[4] != 0) | !borrow)
+    }
+
+    // 通常の表現のままの積 ab mod m
+    // abR^(-1) にR^2を掛けて R^(-1) を打ち消す
+    pub fn mul_mod(&self, a: U256, b: U256) -> U256 {
+        self.mul(self.mul(a, b), self.r2)
+    }
+
+    pub fn square(&self, a: U256) -> U256 {
+        self.mul(a, a)
+    }
+
+    // aはモンゴメリ表現、指数は通常の整数
+    // 法が合成数の場合もあるので、指数はm-1で還元しない
+    pub fn pow(&self, a: U256, ex: U256) -> U256 {
+        pow_by_squaring(self.r, a, ex, |x| self.square(x), |x, y| self.mul(x, y))
+    }
+
+    // 法が素数の場合のフェルマーの小定理による逆元 a^(m-2)
+    pub fn inv(&self, a: U256) -> Option<U256> {
+        if a.is_zero() { return None }
+
+        Some(self.pow(a, self.modulus - 2))
+    }
+}
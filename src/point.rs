@@ -1,159 +1,314 @@
 use std::{ops};
-use crate::field_element::{ExpressionError, FieldElement, FieldElementOperation};
+use std::sync::Arc;
+use primitive_types::U256;
+use crate::curve::Curve;
+use crate::field_element::{ExpressionError, Field};
+use crate::jacobian::JacobianPoint;
+
+// 点の座標
+// 無限遠点は座標を持たないので、片方の座標だけを持つ状態は表現できない
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Coordinates<F: Field> {
+    Infinity,
+    Affine { x: F, y: F },
+}
 
 #[derive(Debug)]
 pub struct Point<T>
 where
-    T: FieldElementOperation,
+    T: Field,
 {
-    x: Option<T>,
-    y: Option<T>,
-    a: T,
-    b: T,
+    coords: Coordinates<T>,
+    curve: Arc<Curve<T>>,
 }
 
-impl Point<FieldElement> {
-    pub fn new(x: Option<FieldElement>, y: Option<FieldElement>, a: FieldElement, b: FieldElement) -> Result<Self, ExpressionError> {
-        if x.is_none() || y.is_none() {
-            return Ok(Self { x, y, a, b });
+impl<F: Field> Point<F> {
+    pub fn new(x: F, y: F, curve: &Arc<Curve<F>>) -> Result<Self, ExpressionError> {
+        // 異なる体の元も曲線上にない点として扱う
+        if !curve.contains(x, y).unwrap_or(false) {
+            return Err(ExpressionError::InvalidPoint);
         }
+        Ok(Self { coords: Coordinates::Affine { x, y }, curve: Arc::clone(curve) })
+    }
 
-        let x = x.unwrap();
-        let y = y.unwrap();
-        
-        let ax = match a * x {
-            Ok(result) => result,
-            Err(_) => return Err(ExpressionError::InvalidPoint),
-        };
-        let x_pow_3_add_ax = match x.pow(3) + ax {
-            Ok(result) => result,
-            Err(_) => return Err(ExpressionError::InvalidPoint),
-        };
-        let x_pow_3_add_ax_add_b = match x_pow_3_add_ax + b {
-            Ok(result) => result,
-            Err(_) => return Err(ExpressionError::InvalidPoint),
-        };
-        
-        if y.pow(2) != x_pow_3_add_ax_add_b {
-            return Err(ExpressionError::InvalidPoint);
+    pub fn infinity(curve: &Arc<Curve<F>>) -> Self {
+        Self { coords: Coordinates::Infinity, curve: Arc::clone(curve) }
+    }
+
+    pub fn from_coordinates(coords: Coordinates<F>, curve: &Arc<Curve<F>>) -> Result<Self, ExpressionError> {
+        match coords {
+            Coordinates::Infinity => Ok(Self::infinity(curve)),
+            Coordinates::Affine { x, y } => Self::new(x, y, curve),
+        }
+    }
+
+    // 曲線の生成点 (登録されていなければNone)
+    pub fn generator(curve: &Arc<Curve<F>>) -> Option<Self> {
+        let g = curve.generator?;
+        Some(Self { coords: Coordinates::Affine { x: g.x, y: g.y }, curve: Arc::clone(curve) })
+    }
+
+    pub fn coordinates(&self) -> Coordinates<F> {
+        self.coords
+    }
+
+    pub fn is_infinity(&self) -> bool {
+        self.coords == Coordinates::Infinity
+    }
+
+    // 無限遠点ではNone
+    pub fn x(&self) -> Option<F> {
+        match self.coords {
+            Coordinates::Affine { x, .. } => Some(x),
+            Coordinates::Infinity => None,
+        }
+    }
+
+    pub fn y(&self) -> Option<F> {
+        match self.coords {
+            Coordinates::Affine { y, .. } => Some(y),
+            Coordinates::Infinity => None,
         }
-        Ok(Self { x: Some(x), y: Some(y), a, b })
+    }
+
+    pub fn curve(&self) -> &Arc<Curve<F>> {
+        &self.curve
+    }
+
+    // -(x, y) = (x, -y)
+    pub fn negate(&self) -> Self {
+        let coords = match self.coords {
+            Coordinates::Affine { x, y } => Coordinates::Affine { x, y: y.neg() },
+            Coordinates::Infinity => Coordinates::Infinity,
+        };
+        Point { coords, curve: Arc::clone(&self.curve) }
+    }
+
+    pub fn double(&self) -> Result<Self, ExpressionError> {
+        JacobianPoint::from_affine(self).double()?.to_affine()
+    }
+
+    // curve上の点の和 (空のイテレータの和は無限遠点)
+    // 途中の和はヤコビアン座標で持ち、逆元の計算は最後の1回だけにする
+    pub fn sum_on<'a, I: IntoIterator<Item = &'a Self>>(curve: &Arc<Curve<F>>, iter: I) -> Result<Self, ExpressionError>
+    where
+        F: 'a,
+    {
+        iter.into_iter().try_fold(JacobianPoint::infinity(curve), |acc, p| acc.add_affine(p))?.to_affine()
+    }
+
+    // 曲線が分からないので、空のイテレータの和はErr(EmptyIterator)を返す
+    pub fn try_sum<'a, I: IntoIterator<Item = &'a Self>>(iter: I) -> Result<Self, ExpressionError>
+    where
+        F: 'a,
+    {
+        let mut iter = iter.into_iter();
+        let first = iter.next().ok_or(ExpressionError::EmptyIterator)?;
+        Self::sum_on(first.curve(), std::iter::once(first).chain(iter))
     }
 }
 
 pub trait PointOperation {
     type Output;
     fn add_op(&self, rhs: &Self) -> Self::Output;
-    fn mul_op(&self, rhs: u32) -> Self::Output;
+    fn mul_op(&self, rhs: U256) -> Self::Output;
 }
-impl PointOperation for Point<FieldElement> {
+impl<F: Field> PointOperation for Point<F> {
     type Output = Result<Self, ExpressionError>;
 
     fn add_op(&self, rhs: &Self) -> Self::Output {
-        if self.a != rhs.a || self.b != rhs.b {
+        if !self.curve.same_equation(&rhs.curve) {
             return Err(ExpressionError::DifferentCurves);
         }
 
-        if self.x.is_none() || self.y.is_none() {
-            return Ok(rhs.clone());
-        }
-        if rhs.x.is_none() || rhs.y.is_none() {
-            return Ok(self.clone());
-        }
-
-        let x1 = self.x.unwrap();
-        let y1 = self.y.unwrap();
-        let x2 = rhs.x.unwrap();
-        let y2 = rhs.y.unwrap();
+        let (x1, y1, x2, y2) = match (self.coords, rhs.coords) {
+            (Coordinates::Infinity, _) => return Ok(rhs.clone()),
+            (_, Coordinates::Infinity) => return Ok(self.clone()),
+            (Coordinates::Affine { x: x1, y: y1 }, Coordinates::Affine { x: x2, y: y2 }) => (x1, y1, x2, y2),
+        };
 
-        let x1_sub_x2 = (x1 - x2)?;
-        let y1_sub_y2 = (y1 - y2)?;
-        let x1_add_x2 = (x1 + x2)?;
+        let x1_add_x2 = x1.try_add(x2)?;
 
         if x1 == x2 && y1 != y2 {
             // y軸対称
 
-            return Ok(Point { x: None, y: None, a: self.a, b: self.b });
+            Ok(Point::infinity(&self.curve))
         } else if x1 == x2 && y1 == y2 {
             // 同じ点同士の加算 -> 接線
-            
-            let three = FieldElement::new(3, x1.prime).unwrap();
-            let two = FieldElement::new(2, x1.prime).unwrap();
-            let zero = FieldElement::new(0, x1.prime).unwrap();
 
             // 接線が垂直
-            if y1 == zero {
-                return Ok(Point::new(None, None, self.a, self.b).unwrap());
+            if y1.is_zero() {
+                return Ok(Point::infinity(&self.curve));
             }
 
-            let s = (((three * x1.pow(2))? + self.a)? / (two * y1)?)?;
+            // s = (3x1^2 + a) / 2y1
+            let x1_pow_2 = x1.square();
+            let s = x1_pow_2.double().try_add(x1_pow_2)?.try_add(self.curve.a)?.try_div(y1.double())?;
             // s^2 - x1 - x2 (分配法則が成り立つので、-(x1+x2)としている)
-            let x3 = (s.pow(2) - x1_add_x2)?;
-            let y3 = ((s * (x1 - x3)?)? - y1)?;
-            
-            Ok(Point::new(Some(x3), Some(y3), self.a, self.b)?)
+            let x3 = s.square().try_sub(x1_add_x2)?;
+            let y3 = s.try_mul(x1.try_sub(x3)?)?.try_sub(y1)?;
+
+            Ok(Point::new(x3, y3, &self.curve)?)
         } else {
             // x値の異なる点同士の加算
 
-            let s = (y1_sub_y2 / x1_sub_x2)?;
+            let s = y1.try_sub(y2)?.try_div(x1.try_sub(x2)?)?;
             // s^2 - x1 - x2 (分配法則が成り立つので、-(x1+x2)としている)
-            let x3 = (s.pow(2) - x1_add_x2)?;
-            let y3 = ((s * (x1 - x3)?)? - y1)?;
-            
-            Ok(Point::new(Some(x3), Some(y3), self.a, self.b)?)
+            let x3 = s.square().try_sub(x1_add_x2)?;
+            let y3 = s.try_mul(x1.try_sub(x3)?)?.try_sub(y1)?;
+
+            Ok(Point::new(x3, y3, &self.curve)?)
         }
     }
 
-    fn mul_op(&self, rhs: u32) -> Self::Output {
-        let mut res = Point::new(None, None, self.a, self.b).unwrap();
-
-        let mut coef = rhs;
-        let mut current = self.clone();
+    // 途中の計算はヤコビアン座標で行い、割り算は最後の1回だけにする
+    fn mul_op(&self, rhs: U256) -> Self::Output {
+        // 補因子が1なら曲線上のすべての点の位数がnを割り切るので、nで還元できる
+        let coef = match self.curve.generator {
+            Some(g) if g.h == U256::one() => rhs % g.n,
+            _ => rhs,
+        };
 
-        while coef > 0 {
-            if coef & 1 == 1 {
-                res = (&res + &current)?;
+        // 上位ビットから2倍算と加算を繰り返す
+        let mut res = JacobianPoint::infinity(&self.curve);
+        for i in (0..coef.bits()).rev() {
+            res = res.double()?;
+            if coef.bit(i) {
+                res = res.add_affine(self)?;
             }
-            current = (&current + &current)?;
-            coef >>= 1;
         }
-        Ok(res)
+        res.to_affine()
     }
 }
 
-impl Clone for Point<FieldElement> {
+impl<F: Field> Clone for Point<F> {
     fn clone(&self) -> Self {
-        Point { x: self.x, y: self.y, a: self.a, b: self.b }
+        Point { coords: self.coords, curve: Arc::clone(&self.curve) }
     }
 }
 
-impl PartialEq for Point<FieldElement> {
+impl<F: Field> PartialEq for Point<F> {
     fn eq(&self, other: &Self) -> bool {
-        self.a == other.a && self.b == other.b && self.x == other.x && self.y == other.y
+        self.curve.same_equation(&other.curve) && self.coords == other.coords
     }
 }
 
-impl ops::Add<&Point<FieldElement>> for &Point<FieldElement> {
-    type Output = Result<Point<FieldElement>, ExpressionError>;
+impl<F: Field> ops::Add<&Point<F>> for &Point<F> {
+    type Output = Result<Point<F>, ExpressionError>;
 
-    fn add(self, rhs: &Point<FieldElement>) -> Self::Output {
+    fn add(self, rhs: &Point<F>) -> Self::Output {
         self.add_op(rhs)
     }
 }
 
+impl<F: Field> ops::Sub<&Point<F>> for &Point<F> {
+    type Output = Result<Point<F>, ExpressionError>;
 
-impl ops::Mul<u32> for &Point<FieldElement> {
-    type Output = Result<Point<FieldElement>, ExpressionError>;
+    fn sub(self, rhs: &Point<F>) -> Self::Output {
+        self.add_op(&rhs.negate())
+    }
+}
+
+// 値と参照の組み合わせは参照同士の演算に揃える
+macro_rules! impl_point_owned_op {
+    ($($op:ident, $method:ident);*) => {$(
+        impl<F: Field> ops::$op<&Point<F>> for Point<F> {
+            type Output = Result<Point<F>, ExpressionError>;
+
+            fn $method(self, rhs: &Point<F>) -> Self::Output {
+                ops::$op::$method(&self, rhs)
+            }
+        }
+
+        impl<F: Field> ops::$op<Point<F>> for &Point<F> {
+            type Output = Result<Point<F>, ExpressionError>;
 
-    fn mul(self, rhs: u32) -> Self::Output {
-        self.mul_op(rhs)
+            fn $method(self, rhs: Point<F>) -> Self::Output {
+                ops::$op::$method(self, &rhs)
+            }
+        }
+
+        impl<F: Field> ops::$op for Point<F> {
+            type Output = Result<Point<F>, ExpressionError>;
+
+            fn $method(self, rhs: Point<F>) -> Self::Output {
+                ops::$op::$method(&self, &rhs)
+            }
+        }
+    )*};
+}
+
+impl_point_owned_op!(Add, add; Sub, sub);
+
+impl<F: Field> ops::Neg for &Point<F> {
+    type Output = Point<F>;
+
+    fn neg(self) -> Self::Output {
+        self.negate()
     }
 }
 
-impl ops::Mul<&Point<FieldElement>> for u32 {
-    type Output = Result<Point<FieldElement>, ExpressionError>;
+impl<F: Field> ops::Neg for Point<F> {
+    type Output = Point<F>;
 
-    fn mul(self, rhs: &Point<FieldElement>) -> Self::Output {
-        rhs * self  // 既に実装した Point * u32 を再利用
+    fn neg(self) -> Self::Output {
+        self.negate()
     }
-}
\ No newline at end of file
+}
+
+// 代入演算は結果を返せないので、異なる曲線の点同士ではpanicする
+macro_rules! impl_point_assign_op {
+    ($($op:ident, $method:ident, $base:ident, $base_method:ident);*) => {$(
+        impl<F: Field> ops::$op<&Point<F>> for Point<F> {
+            fn $method(&mut self, rhs: &Point<F>) {
+                *self = ops::$base::$base_method(&*self, rhs).unwrap_or_else(|e| panic!("{}", e));
+            }
+        }
+
+        impl<F: Field> ops::$op for Point<F> {
+            fn $method(&mut self, rhs: Point<F>) {
+                ops::$op::$method(self, &rhs);
+            }
+        }
+    )*};
+}
+
+impl_point_assign_op!(AddAssign, add_assign, Add, add; SubAssign, sub_assign, Sub, sub);
+
+// 曲線が分からないので、空のイテレータの和はErr(EmptyIterator)になる
+// iter.sum::<Result<_, _>>()? と書ける (曲線が決まっている場合は Point::sum_on を使う)
+impl<'a, F: Field + 'a> std::iter::Sum<&'a Point<F>> for Result<Point<F>, ExpressionError> {
+    fn sum<I: Iterator<Item = &'a Point<F>>>(iter: I) -> Self {
+        Point::try_sum(iter)
+    }
+}
+
+impl<F: Field> std::iter::Sum<Point<F>> for Result<Point<F>, ExpressionError> {
+    fn sum<I: Iterator<Item = Point<F>>>(mut iter: I) -> Self {
+        let first = iter.next().ok_or(ExpressionError::EmptyIterator)?;
+        iter.try_fold(JacobianPoint::from_affine(&first), |acc, p| acc.add_affine(&p))?.to_affine()
+    }
+}
+
+// u32でもU256でも掛けられるようにする
+macro_rules! impl_point_scalar_mul {
+    ($($t:ty),*) => {$(
+        impl<F: Field> ops::Mul<$t> for &Point<F> {
+            type Output = Result<Point<F>, ExpressionError>;
+
+            fn mul(self, rhs: $t) -> Self::Output {
+                self.mul_op(U256::from(rhs))
+            }
+        }
+
+        impl<F: Field> ops::Mul<&Point<F>> for $t {
+            type Output = Result<Point<F>, ExpressionError>;
+
+            fn mul(self, rhs: &Point<F>) -> Self::Output {
+                rhs * self  // 既に実装した Point * scalar を再利用
+            }
+        }
+    )*};
+}
+
+impl_point_scalar_mul!(u32, U256);
@@ -0,0 +1,198 @@
+use std::ops;
+use crate::field_element::{ExpressionError, Field};
+
+// 体Fの元を係数とする多項式
+// 係数は次数の低い順に持ち、最高次の係数が0にならないように正規化する
+// (零多項式は係数を持たない)
+#[derive(Debug, Clone, PartialEq)]
+pub struct Polynomial<F: Field> {
+    coeffs: Vec<F>,
+}
+
+impl<F: Field> Polynomial<F> {
+    pub fn new(mut coeffs: Vec<F>) -> Self {
+        while coeffs.last().is_some_and(|c| c.is_zero()) {
+            coeffs.pop();
+        }
+        Polynomial { coeffs }
+    }
+
+    pub fn zero() -> Self {
+        Polynomial { coeffs: Vec::new() }
+    }
+
+    // 定数多項式 c
+    pub fn constant(c: F) -> Self {
+        Self::new(vec![c])
+    }
+
+    // x - a
+    pub fn linear_root(a: F) -> Self {
+        Self::new(vec![a.neg(), a.one()])
+    }
+
+    pub fn coeffs(&self) -> &[F] {
+        &self.coeffs
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.coeffs.is_empty()
+    }
+
+    // 零多項式の次数はNone
+    pub fn degree(&self) -> Option<usize> {
+        self.coeffs.len().checked_sub(1)
+    }
+
+    pub fn leading(&self) -> Option<F> {
+        self.coeffs.last().copied()
+    }
+
+    // ホーナー法
+    pub fn eval(&self, x: F) -> Result<F, ExpressionError> {
+        self.coeffs.iter().rev().try_fold(x.zero(), |acc, &c| acc.try_mul(x)?.try_add(c))
+    }
+
+    pub fn try_add(&self, rhs: &Self) -> Result<Self, ExpressionError> {
+        self.zip_coeffs(rhs, |a, b| a.try_add(b))
+    }
+
+    pub fn try_sub(&self, rhs: &Self) -> Result<Self, ExpressionError> {
+        self.zip_coeffs(rhs, |a, b| a.try_sub(b))
+    }
+
+    // 短い方には0があるものとして係数毎に計算する
+    fn zip_coeffs(&self, rhs: &Self, op: impl Fn(F, F) -> Result<F, ExpressionError>) -> Result<Self, ExpressionError> {
+        let zero = match self.coeffs.first().or(rhs.coeffs.first()) {
+            Some(c) => c.zero(),
+            None => return Ok(Self::zero()),
+        };
+
+        let len = self.coeffs.len().max(rhs.coeffs.len());
+        let coeffs = (0..len)
+            .map(|i| op(*self.coeffs.get(i).unwrap_or(&zero), *rhs.coeffs.get(i).unwrap_or(&zero)))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(coeffs))
+    }
+
+    pub fn try_mul(&self, rhs: &Self) -> Result<Self, ExpressionError> {
+        if self.is_zero() || rhs.is_zero() { return Ok(Self::zero()) }
+
+        let mut coeffs = vec![self.coeffs[0].zero(); self.coeffs.len() + rhs.coeffs.len() - 1];
+        for (i, &a) in self.coeffs.iter().enumerate() {
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                coeffs[i + j] = coeffs[i + j].try_add(a.try_mul(b)?)?;
+            }
+        }
+        Ok(Self::new(coeffs))
+    }
+
+    // 各係数をc倍する
+    pub fn scale(&self, c: F) -> Result<Self, ExpressionError> {
+        let coeffs = self.coeffs.iter().map(|&a| a.try_mul(c)).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(coeffs))
+    }
+
+    // self = q * rhs + r (deg r < deg rhs) となる (q, r)
+    pub fn divrem(&self, rhs: &Self) -> Result<(Self, Self), ExpressionError> {
+        let lead_inv = rhs.leading().ok_or(ExpressionError::ZeroDivision)?
+            .inv().ok_or(ExpressionError::ZeroDivision)?;
+        let rhs_len = rhs.coeffs.len();
+        if self.coeffs.len() < rhs_len { return Ok((Self::zero(), self.clone())) }
+
+        // 筆算と同じく、最高次の項から順に消していく
+        let mut rem = self.coeffs.clone();
+        let mut quot = vec![lead_inv.zero(); self.coeffs.len() - rhs_len + 1];
+        for i in (0..quot.len()).rev() {
+            let q = rem[i + rhs_len - 1].try_mul(lead_inv)?;
+            for (j, &b) in rhs.coeffs.iter().enumerate() {
+                rem[i + j] = rem[i + j].try_sub(q.try_mul(b)?)?;
+            }
+            quot[i] = q;
+        }
+        rem.truncate(rhs_len - 1);
+
+        Ok((Self::new(quot), Self::new(rem)))
+    }
+
+    pub fn derivative(&self) -> Result<Self, ExpressionError> {
+        let coeffs = self.coeffs.iter().enumerate().skip(1)
+            .map(|(i, &c)| mul_int(c, i))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::new(coeffs))
+    }
+
+    // 最高次の係数を1にする
+    pub fn monic(&self) -> Result<Self, ExpressionError> {
+        match self.leading() {
+            Some(lead) => self.scale(lead.inv().ok_or(ExpressionError::ZeroDivision)?),
+            None => Ok(Self::zero()),
+        }
+    }
+
+    // ユークリッドの互除法 (結果はモニック)
+    pub fn gcd(&self, rhs: &Self) -> Result<Self, ExpressionError> {
+        let (mut a, mut b) = (self.clone(), rhs.clone());
+        while !b.is_zero() {
+            let (_, r) = a.divrem(&b)?;
+            (a, b) = (b, r);
+        }
+        a.monic()
+    }
+
+    // ラグランジュ補間: 点 (x_i, y_i) をすべて通る次数 len-1 以下の多項式
+    // 同じx座標の点があるとErr(ZeroDivision)
+    pub fn interpolate(points: &[(F, F)]) -> Result<Self, ExpressionError> {
+        let mut res = Self::zero();
+
+        for (i, &(xi, yi)) in points.iter().enumerate() {
+            // y_i * Π (x - x_j) / (x_i - x_j)
+            let mut basis = Self::constant(xi.one());
+            let mut denom = xi.one();
+            for (j, &(xj, _)) in points.iter().enumerate() {
+                if i == j { continue }
+                basis = basis.try_mul(&Self::linear_root(xj))?;
+                denom = denom.try_mul(xi.try_sub(xj)?)?;
+            }
+            res = res.try_add(&basis.scale(yi.try_div(denom)?)?)?;
+        }
+        Ok(res)
+    }
+}
+
+// c * k (cをk回足したもの)
+fn mul_int<F: Field>(c: F, k: usize) -> Result<F, ExpressionError> {
+    let mut res = c.zero();
+    let mut current = c;
+    let mut k = k;
+    while k > 0 {
+        if k & 1 == 1 { res = res.try_add(current)? }
+        current = current.double();
+        k >>= 1;
+    }
+    Ok(res)
+}
+
+impl<F: Field> ops::Add<&Polynomial<F>> for &Polynomial<F> {
+    type Output = Result<Polynomial<F>, ExpressionError>;
+
+    fn add(self, rhs: &Polynomial<F>) -> Self::Output {
+        self.try_add(rhs)
+    }
+}
+
+impl<F: Field> ops::Sub<&Polynomial<F>> for &Polynomial<F> {
+    type Output = Result<Polynomial<F>, ExpressionError>;
+
+    fn sub(self, rhs: &Polynomial<F>) -> Self::Output {
+        self.try_sub(rhs)
+    }
+}
+
+impl<F: Field> ops::Mul<&Polynomial<F>> for &Polynomial<F> {
+    type Output = Result<Polynomial<F>, ExpressionError>;
+
+    fn mul(self, rhs: &Polynomial<F>) -> Self::Output {
+        self.try_mul(rhs)
+    }
+}
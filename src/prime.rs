@@ -0,0 +1,99 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use primitive_types::U256;
+use crate::field_int::FieldInt;
+use crate::montgomery::Montgomery;
+use crate::rng::XorShift64;
+
+// 最初の12個の素数を底にすると n < 3.18 * 10^23 (64bitを含む) で決定的に判定できる
+const BASES: [u64; 12] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37];
+
+// それより大きい場合に追加で試すランダムな底の数
+// 底はプロセス毎のエントロピーから選ぶので、どんなnでも誤判定の確率は 4^(-20) 以下
+const RANDOM_ROUNDS: usize = 20;
+
+// 64bit以下の素数判定 (コンパイル時に使えるように、BASESによる決定的なミラー・ラビンをconst fnで書く)
+pub const fn is_prime_u64(n: u64) -> bool {
+    if n < 2 { return false }
+
+    let mut i = 0;
+    while i < BASES.len() {
+        if n == BASES[i] { return true }
+        if n.is_multiple_of(BASES[i]) { return false }
+        i += 1;
+    }
+
+    // n - 1 = d * 2^s (dは奇数)
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+
+    let mut i = 0;
+    while i < BASES.len() {
+        let mut x = pow_mod_u64(BASES[i], d, n);
+        if x != 1 && x != n - 1 {
+            let mut r = 1;
+            while r < s && x != n - 1 {
+                x = mul_mod_u64(x, x, n);
+                r += 1;
+            }
+            if x != n - 1 { return false }
+        }
+        i += 1;
+    }
+    true
+}
+
+const fn mul_mod_u64(a: u64, b: u64, m: u64) -> u64 {
+    (a as u128 * b as u128 % m as u128) as u64
+}
+
+const fn pow_mod_u64(a: u64, mut ex: u64, m: u64) -> u64 {
+    let mut res = 1;
+    let mut base = a % m;
+    while ex > 0 {
+        if ex & 1 == 1 { res = mul_mod_u64(res, base, m) }
+        base = mul_mod_u64(base, base, m);
+        ex >>= 1;
+    }
+    res
+}
+
+// ミラー・ラビン素数判定
+pub fn is_prime<T: FieldInt>(n: T) -> bool {
+    if n < T::from_u64(2) { return false }
+
+    let m = n.to_u256();
+    for &p in &BASES {
+        if m == U256::from(p) { return true }
+        if (m % p).is_zero() { return false }
+    }
+
+    // n - 1 = d * 2^s (dは奇数)
+    let s = (m - 1).trailing_zeros();
+    let d = (m - 1) >> s;
+
+    // nは奇数なので、モンゴメリ表現で計算する
+    let mont = Montgomery::new(m);
+    let one = mont.one();
+    let minus_one = m - one;
+
+    // aがnの合成数の証拠になっているか
+    let is_witness = |a: U256| {
+        let mut x = mont.pow(mont.to_montgomery(a), d);
+        if x == one || x == minus_one { return false }
+
+        for _ in 1..s {
+            x = mont.square(x);
+            if x == minus_one { return false }
+        }
+        true
+    };
+
+    if BASES.iter().any(|&a| is_witness(U256::from(a))) { return false }
+    if m.bits() <= 64 { return true }
+
+    // 2 <= a <= n - 2
+    // nだけから底を決めると、底を知っている相手が誤判定される合成数を作れる
+    let mut rng = XorShift64::new(RandomState::new().build_hasher().finish() ^ m.low_u64());
+    (0..RANDOM_ROUNDS).all(|_| !is_witness(rng.below(m - 3) + 2))
+}
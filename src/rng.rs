@@ -0,0 +1,27 @@
+use primitive_types::U256;
+
+// 乱択アルゴリズム用の疑似乱数 (xorshift64)
+// 予測可能なので、秘密鍵などの生成には使わない
+#[derive(Debug, Clone)]
+pub struct XorShift64 {
+    state: u64,
+}
+
+impl XorShift64 {
+    pub fn new(seed: u64) -> Self {
+        // 状態が0だと0しか出てこない
+        XorShift64 { state: if seed == 0 { 0x9E3779B97F4A7C15 } else { seed } }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // 0 <= n < m (mが2^256に比べて十分小さくない場合は僅かに偏る)
+    pub fn below(&mut self, m: U256) -> U256 {
+        U256([self.next_u64(), self.next_u64(), self.next_u64(), self.next_u64()]) % m
+    }
+}
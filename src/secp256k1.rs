@@ -1,73 +1,412 @@
 use std::ops;
-use crate::field_element::{FieldElement, FieldElementOperation};
-use crate::field_element::{ExpressionError, Modulus};
-use crate::point::{Point, PointOperation};
-use primitive_types::U256;
-
-impl Modulus for U256 {
-    fn modulus(self, prime: u32) -> Self {
-        self % U256::from(prime)
-    }
-}
+use std::sync::{Arc, OnceLock};
+use crate::curve::{Curve, CurveParams};
+use crate::field_element::{pow_by_squaring, Exponent, Field, FieldElementOperation, PrimeField};
+use crate::field_element::{impl_field_num_traits, ExpressionError};
+use crate::montgomery::Montgomery;
+use crate::point::{Coordinates, Point};
+use crate::{ct, gcd};
+use primitive_types::{U256, U512};
+
+// p = 2^256 - 2^32 - 977
+pub const P: U256 = U256([
+    0xFFFFFFFEFFFFFC2F,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+    0xFFFFFFFFFFFFFFFF,
+]);
+
+// 生成点Gの位数
+pub const N: U256 = U256([
+    0xBFD25E8CD0364141,
+    0xBAAEDCE6AF48A03B,
+    0xFFFFFFFFFFFFFFFE,
+    0xFFFFFFFFFFFFFFFF,
+]);
+
+// 生成点G
+pub const GX: U256 = U256([
+    0x59F2815B16F81798,
+    0x029BFCDB2DCE28D9,
+    0x55A06295CE870B07,
+    0x79BE667EF9DCBBAC,
+]);
+pub const GY: U256 = U256([
+    0x9C47D08FFB10D4B8,
+    0xFD17B448A6855419,
+    0x5DA4FBFC0E1108A8,
+    0x483ADA7726A3C465,
+]);
+
+// pを法とするモンゴメリ表現のパラメータ
+// R mod p = 2^32 + 977, R^2 mod p = (2^32 + 977)^2
+pub const P_MONTGOMERY: Montgomery = Montgomery::from_parts(
+    P,
+    0xD838091DD2253531,
+    U256([0x00000001000003D1, 0, 0, 0]),
+    U256([0x000007A2000E90A1, 0x0000000000000001, 0, 0]),
+);
+
+// Nを法とするモンゴメリ表現のパラメータ
+// R mod n = 2^256 - n, R^2 mod n = (2^256 - n)^2
+pub const N_MONTGOMERY: Montgomery = Montgomery::from_parts(
+    N,
+    0x4B0DFF665588B13F,
+    U256([0x402DA1732FC9BEBF, 0x4551231950B75FC4, 0x0000000000000001, 0]),
+    U256([0x896CF21467D7D140, 0x741496C20E7CF878, 0xE697F5E45BCD07C6, 0x9D671CD581C69BC5]),
+);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct S256Field {
     pub num: U256,
-    pub prime: u32,
+    pub prime: U256,
 }
 
 impl S256Field { 
-    pub const fn new(num: U256) -> Self {
+    pub const ZERO: Self = Self::from_reduced(U256::zero());
+    pub const ONE: Self = Self::from_reduced(U256::one());
+
+    // num >= p はErr(InvalidFieldElement) (還元はしない)
+    pub fn new(num: U256) -> Result<Self, ExpressionError> {
+        if num >= P { return Err(ExpressionError::InvalidFieldElement) }
+
+        Ok(Self::from_reduced(num))
+    }
+
+    // 0 <= num < p が分かっている内部の計算結果から作る (検証しない)
+    pub(crate) const fn from_reduced(num: U256) -> Self {
         S256Field {
-            num: num,
-            prime: 2u32.pow(256) - 2u32.pow(32) - 977,
+            num,
+            prime: P,
+        }
+    }
+
+    // ルジャンドル記号 (a/p) : 0, 1 (平方剰余), -1 (平方非剰余)
+    pub fn legendre(&self) -> i32 {
+        if self.is_zero() { return 0 }
+
+        // オイラーの規準 a^((p-1)/2) = ±1
+        if self.pow((self.prime - 1) >> 1) == Self::ONE { 1 } else { -1 }
+    }
+
+    pub fn is_quadratic_residue(&self) -> bool {
+        self.legendre() != -1
+    }
+
+    pub fn sqrt(&self) -> Option<Self> {
+        // p = 3 (mod 4) なので a^((p+1)/4) が平方根の候補になる
+        let root = self.pow((self.prime + 1) >> 2);
+
+        if root.square() == *self { Some(root) } else { None }
+    }
+
+    // choiceが真ならb、偽ならa (分岐しない)
+    pub fn conditional_select(a: &Self, b: &Self, choice: bool) -> Self {
+        Self::from_reduced(ct::select_u256(a.num, b.num, choice))
+    }
+
+    // choiceが真ならaとbを入れ替える (分岐しない)
+    pub fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        ct::swap_u256(&mut a.num, &mut b.num, choice);
+    }
+
+    pub fn ct_eq(&self, other: &Self) -> bool {
+        ct::eq_u256(self.num, other.num)
+    }
+}
+
+// 逆元の計算方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Inversion {
+    // フェルマーの小定理 a^(p-2)
+    Fermat,
+    // 拡張ユークリッドの互除法 (速いが実行時間が値に依存するので、公開データ向け)
+    Euclid,
+    // Bernstein-Yang の safegcd (定数時間なので、秘密のデータ向け)
+    SafeGcd,
+}
+
+impl S256Field {
+    pub fn inv_with(&self, method: Inversion) -> Option<Self> {
+        if self.is_zero() { return None }
+
+        match method {
+            Inversion::Fermat => Some(self.pow(-1)),
+            Inversion::Euclid => gcd::inv_mod(self.num, P).map(Self::from_reduced),
+            Inversion::SafeGcd => Some(Self::from_reduced(safegcd_inv(self.num))),
+        }
+    }
+}
+
+// 2^256 mod p = 2^32 + 977
+const P_C: u64 = 0x1000003D1;
+
+// 64bit×4 の加算と桁上がり
+fn add_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut r = [0u64; 4];
+    let mut carry = 0u128;
+    for (i, limb) in r.iter_mut().enumerate() {
+        let sum = a[i] as u128 + b[i] as u128 + carry;
+        *limb = sum as u64;
+        carry = sum >> 64;
+    }
+    (r, carry as u64)
+}
+
+// 64bit×4 の減算と桁借り
+fn sub_limbs(a: &[u64; 4], b: &[u64; 4]) -> ([u64; 4], u64) {
+    let mut r = [0u64; 4];
+    let mut borrow = 0u64;
+    for (i, limb) in r.iter_mut().enumerate() {
+        let (d1, b1) = a[i].overflowing_sub(b[i]);
+        let (d2, b2) = d1.overflowing_sub(borrow);
+        *limb = d2;
+        borrow = (b1 | b2) as u64;
+    }
+    (r, borrow)
+}
+
+// 0 <= a < 2p (carryは2^256の桁) をpで正規化する
+fn normalize(a: [u64; 4], carry: u64) -> U256 {
+    normalize_mod(a, carry, P)
+}
+
+// 0 <= a < 2m をmで正規化する
+// 分岐せずに a - m と a のどちらかを選ぶ
+fn normalize_mod(a: [u64; 4], carry: u64, m: U256) -> U256 {
+    let (diff, borrow) = sub_limbs(&a, &m.0);
+    // 2^256を超えているか、桁借りしなければ a >= m
+    ct::select_u256(U256(a), U256(diff), (carry | (borrow ^ 1)) == 1)
+}
+
+// 64bit×4 の積 (512bit)
+fn mul_wide(a: &[u64; 4], b: &[u64; 4]) -> [u64; 8] {
+    let mut t = [0u64; 8];
+    for (i, &ai) in a.iter().enumerate() {
+        let mut carry = 0u128;
+        for (j, &bj) in b.iter().enumerate() {
+            let sum = t[i + j] as u128 + ai as u128 * bj as u128 + carry;
+            t[i + j] = sum as u64;
+            carry = sum >> 64;
+        }
+        t[i + 4] = carry as u64;
+    }
+    t
+}
+
+// 二乗は a_i * a_j (i < j) を一度だけ計算して2倍する
+fn square_wide(a: &[u64; 4]) -> [u64; 8] {
+    let mut t = [0u64; 8];
+    for i in 0..4 {
+        let mut carry = 0u128;
+        for j in (i + 1)..4 {
+            let sum = t[i + j] as u128 + a[i] as u128 * a[j] as u128 + carry;
+            t[i + j] = sum as u64;
+            carry = sum >> 64;
         }
+        t[i + 4] = carry as u64;
+    }
+
+    // 2倍して対角成分 a_i^2 を足す
+    let mut shifted = 0u64;
+    let mut carry = 0u128;
+    for (i, limb) in t.iter_mut().enumerate() {
+        let doubled = (*limb << 1) | shifted;
+        shifted = *limb >> 63;
+        let square = a[i / 2] as u128 * a[i / 2] as u128;
+        let diag = if i % 2 == 0 { square as u64 } else { (square >> 64) as u64 };
+        let sum = doubled as u128 + diag as u128 + carry;
+        *limb = sum as u64;
+        carry = sum >> 64;
     }
+    t
+}
+
+// 512bitの値をpで還元する
+// t = lo + hi * 2^256 = lo + hi * (2^32 + 977) (mod p) を2回繰り返す
+fn reduce_wide(t: [u64; 8]) -> U256 {
+    // 1回目: lo + hi * c (最大で 256 + 34 bit)
+    let mut r = [0u64; 4];
+    let mut carry = 0u128;
+    for (i, limb) in r.iter_mut().enumerate() {
+        let sum = t[i] as u128 + t[i + 4] as u128 * P_C as u128 + carry;
+        *limb = sum as u64;
+        carry = sum >> 64;
+    }
+
+    // 2回目: 溢れた上位 (34bit以内) に c を掛けて足す
+    let mut carry = carry * P_C as u128;
+    for limb in r.iter_mut() {
+        let sum = *limb as u128 + carry;
+        *limb = sum as u64;
+        carry = sum >> 64;
+    }
+
+    // ここでまだ溢れた場合、下位は小さいので c を足しても溢れない
+    // 分岐しないように常に carry * c を足す
+    let (r, _) = add_limbs(&r, &[carry as u64 * P_C, 0, 0, 0]);
+
+    // 0 <= r < 2^256 < 2p なので、pを一度引けば正規化できる
+    normalize(r, 0)
+}
+
+// 256bitの入力に対するdivstepの回数の上限 floor((49 * 256 + 57) / 17)
+const DIVSTEPS: usize = 741;
+
+// a / 2 mod p (奇数ならpを足してから割る)
+fn half_mod_p(a: U256) -> U256 {
+    let m = ct::mask(a.bit(0));
+    let p = [P.0[0] & m, P.0[1] & m, P.0[2] & m, P.0[3] & m];
+    let (sum, carry) = add_limbs(&a.0, &p);
+    U256([
+        (sum[0] >> 1) | (sum[1] << 63),
+        (sum[1] >> 1) | (sum[2] << 63),
+        (sum[2] >> 1) | (sum[3] << 63),
+        (sum[3] >> 1) | (carry << 63),
+    ])
+}
+
+// f, g は符号付きなので、U512の2の補数で表す
+fn neg_u512(a: U512) -> U512 {
+    (!a).overflowing_add(U512::one()).0
+}
+
+fn select_u512(a: U512, b: U512, choice: bool) -> U512 {
+    let m = U512([ct::mask(choice); 8]);
+    a ^ (m & (a ^ b))
+}
+
+// 算術右シフト (符号を保ったまま2で割る)
+fn sar1_u512(a: U512) -> U512 {
+    (a >> 1) | (a & (U512::one() << 511))
+}
+
+// safegcd による a^(-1) mod p
+// 値によらず同じ回数のdivstepを分岐せずに繰り返す
+// d * a = f, e * a = g (mod p) を保ったまま g を0に、f を ±1 にする
+fn safegcd_inv(a: U256) -> U256 {
+    let mut delta = 1i64;
+    let mut f = U512::from(P);
+    let mut g = U512::from(a);
+    let mut d = S256Field::ZERO;
+    let mut e = S256Field::ONE;
+
+    for _ in 0..DIVSTEPS {
+        // delta > 0 かつ g が奇数なら (delta, f, g, d, e) -> (-delta, g, -f, e, -d)
+        let swap = (delta > 0) & g.bit(0);
+        let neg_f = neg_u512(f);
+        let neg_d = S256Field::ZERO.sub_op(d);
+        f = select_u512(f, g, swap);
+        g = select_u512(g, neg_f, swap);
+        d = S256Field::conditional_select(&d, &e, swap);
+        e = S256Field::conditional_select(&e, &neg_d, swap);
+        delta ^= (delta ^ delta.wrapping_neg()) & ct::mask(swap) as i64;
+
+        // g が奇数なら f を足して (fは常に奇数) 2で割る
+        let odd = g.bit(0);
+        delta += 1;
+        g = sar1_u512(g.overflowing_add(select_u512(U512::zero(), f, odd)).0);
+        e = S256Field::from_reduced(half_mod_p(e.add_op(S256Field::conditional_select(&S256Field::ZERO, &d, odd)).num));
+    }
+
+    // f = ±1 なので、符号を合わせる
+    let neg_d = S256Field::ZERO.sub_op(d);
+    S256Field::conditional_select(&d, &neg_d, f.bit(511)).num
 }
 
 impl FieldElementOperation for S256Field {
     type GeneralOpOutput = Self;
 
     fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
-        Self { 
-            num: (self.num + rhs.num).modulus(self.prime),
-            prime: self.prime
-        }
+        let (sum, carry) = add_limbs(&self.num.0, &rhs.num.0);
+
+        Self::from_reduced(normalize(sum, carry))
     }
 
     fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput {
-        Self { 
-            num: (self.num - rhs.num).modulus(self.prime),
-            prime: self.prime
-        }
+        // 桁借りが発生した場合だけpを足して戻す
+        let (diff, borrow) = sub_limbs(&self.num.0, &rhs.num.0);
+        let p = ct::select_u256(U256::zero(), P, borrow == 1);
+
+        Self::from_reduced(U256(add_limbs(&diff, &p.0).0))
     }
 
     fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput {
-        Self { 
-            num: (self.num * rhs.num).modulus(self.prime),
-            prime: self.prime
-        }
+        Self::from_reduced(reduce_wide(mul_wide(&self.num.0, &rhs.num.0)))
     }
 
+    // 結果を返せないので、0で割った場合はpanicする (Errが必要ならtry_divを使う)
     fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if rhs.is_zero() { panic!("{}", ExpressionError::ZeroDivision) }
+
         self.mul_op(rhs.pow(-1))
     }
 
-    fn pow(self, rhs: i32) -> Self {
-        let ex = if rhs < 0 {
-            // 指数nが負の場合
-            // 指数が正になるまでa^p-1 (= 1) を掛け合わせるので、
-            // a^n = a^(n mod p-1)
-            rhs.modulus(self.prime - 1)
-        } else { rhs };
-
-        let mut num = U256::from(1);
-        for _ in 0..ex {num = (self.num * num).modulus(self.prime)}
-        
-        Self {
-            num: num,
-            prime: self.prime
-        }
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
+        let ex = rhs.into().reduce(self.prime - 1);
+        pow_by_squaring(Self::ONE, self, ex, |x| x.square(), |x, y| x.mul_op(y))
+    }
+}
+
+impl Field for S256Field {
+    fn zero(&self) -> Self {
+        Self::ZERO
+    }
+
+    fn one(&self) -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    fn characteristic(&self) -> U256 {
+        self.prime
+    }
+
+    fn neg(&self) -> Self {
+        Self::ZERO.sub_op(*self)
+    }
+
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() { return None }
+
+        Some(self.pow(-1))
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.add_op(rhs))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.sub_op(rhs))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.mul_op(rhs))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        if rhs.is_zero() { return Err(ExpressionError::ZeroDivision) }
+
+        Ok(self.div_op(rhs))
+    }
+
+    fn square(&self) -> Self {
+        Self::from_reduced(reduce_wide(square_wide(&self.num.0)))
+    }
+
+    fn double(&self) -> Self {
+        self.add_op(*self)
+    }
+}
+
+impl PrimeField for S256Field {
+    fn from_u256(num: U256, prime: U256) -> Result<Self, ExpressionError> {
+        if prime != P { return Err(ExpressionError::DifferentOrderExpression) }
+
+        Self::new(num)
     }
 }
 
@@ -104,149 +443,447 @@ impl ops::Div for S256Field {
 }
 
 
+impl_field_num_traits!([] S256Field);
+
+impl num_traits::Num for S256Field {
+    type FromStrRadixErr = ExpressionError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        match U256::from_str_radix(str, radix) {
+            Ok(num) => Ok(Self::from_reduced(num % P)),
+            Err(_) => Err(ExpressionError::InvalidFieldElement),
+        }
+    }
+}
+
 impl std::fmt::Display for S256Field {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:064}", self.num)
     }
 }
 
+// モンゴメリ表現 aR mod p で値を持つsecp256k1の体の元
+// 乗算はモンゴメリ乗算1回で済み、通常の表現との変換は入出力の時だけ行う
+// S256Field (pの特殊形による還元) と同じ演算を持つので、Point<S256MontField> のように選んで使える
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S256MontField {
+    mont: U256,
+}
+
+impl S256MontField {
+    pub const ZERO: Self = S256MontField { mont: U256::zero() };
+    pub const ONE: Self = S256MontField { mont: U256([P_C, 0, 0, 0]) };
+
+    pub fn new(num: U256) -> Result<Self, ExpressionError> {
+        if num >= P { return Err(ExpressionError::InvalidFieldElement) }
 
+        Ok(S256MontField { mont: P_MONTGOMERY.to_montgomery(num) })
+    }
 
-#[derive(Debug)]
-pub struct S256Point<T>
-where
-    T: FieldElementOperation,
-{
-    x: Option<T>,
-    y: Option<T>,
-    a: T,
-    b: T,
-    N: U256,
+    // 通常の表現の値
+    pub fn num(&self) -> U256 {
+        P_MONTGOMERY.from_montgomery(self.mont)
+    }
 }
 
-impl Clone for S256Point<S256Field> {
-    fn clone(&self) -> Self {
-        S256Point { x: self.x, y: self.y, a: self.a, b: self.b, N: self.N }
+impl From<S256Field> for S256MontField {
+    fn from(a: S256Field) -> Self {
+        S256MontField { mont: P_MONTGOMERY.to_montgomery(a.num) }
     }
 }
 
-impl PartialEq for S256Point<S256Field> {
-    fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.y == other.y
+impl From<S256MontField> for S256Field {
+    fn from(a: S256MontField) -> Self {
+        S256Field::from_reduced(a.num())
     }
 }
 
-impl S256Point<S256Field> {   
-    pub fn new(x: Option<S256Field>, y: Option<S256Field>) -> Result<Self, ExpressionError> {
-        let a: S256Field = S256Field::new(U256::from(0));
-        let b: S256Field = S256Field::new(U256::from(7));
-        let N = U256::from_dec_str("0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141").unwrap();
-        
-        if x.is_none() || y.is_none() {
-            return Ok(Self { x, y, a, b, N });
-        }
+impl FieldElementOperation for S256MontField {
+    type GeneralOpOutput = Self;
 
-        let x = x.unwrap();
-        let y = y.unwrap();
-        
-        let ax = a * x;
-        let x_pow_3_add_ax = x.pow(3) + ax;
-        let x_pow_3_add_ax_add_b = x_pow_3_add_ax + b;
-        
-        if y.pow(2) != x_pow_3_add_ax_add_b {
-            return Err(ExpressionError::InvalidPoint);
-        }
-        Ok(Self { x: Some(x), y: Some(y), a, b, N })
+    // 加減算はモンゴメリ表現のままでよい (aR + bR = (a + b)R)
+    fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        let (sum, carry) = add_limbs(&self.mont.0, &rhs.mont.0);
+
+        S256MontField { mont: normalize(sum, carry) }
+    }
+
+    fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        let (diff, borrow) = sub_limbs(&self.mont.0, &rhs.mont.0);
+        let p = ct::select_u256(U256::zero(), P, borrow == 1);
+
+        S256MontField { mont: U256(add_limbs(&diff, &p.0).0) }
+    }
+
+    // aR * bR * R^(-1) = abR
+    fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        S256MontField { mont: P_MONTGOMERY.mul(self.mont, rhs.mont) }
+    }
+
+    // 結果を返せないので、0で割った場合はpanicする (Errが必要ならtry_divを使う)
+    fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if rhs.is_zero() { panic!("{}", ExpressionError::ZeroDivision) }
+
+        self.mul_op(rhs.pow(-1))
+    }
+
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
+        let ex = rhs.into().reduce(P - 1);
+
+        S256MontField { mont: P_MONTGOMERY.pow(self.mont, ex) }
     }
 }
 
-impl S256Point<S256Field> {
-    fn add_op(&self, rhs: &Self) -> S256Point<S256Field> {
-        if self.x.is_none() || self.y.is_none() {
-            return rhs.clone();
-        }
-        if rhs.x.is_none() || rhs.y.is_none() {
-            return self.clone();
-        }
+impl Field for S256MontField {
+    fn zero(&self) -> Self {
+        Self::ZERO
+    }
 
-        let x1 = self.x.unwrap();
-        let y1 = self.y.unwrap();
-        let x2 = rhs.x.unwrap();
-        let y2 = rhs.y.unwrap();
-
-        let x1_sub_x2 = x1 - x2;
-        let y1_sub_y2 = y1 - y2;
-        let x1_add_x2 = x1 + x2;
-
-        if x1 == x2 && y1 != y2 {
-            // y軸対称
-            S256Point::new(None, None).unwrap()
-        } else if x1 == x2 && y1 == y2 {
-            // 同じ点同士の加算 -> 接線
-            let three = S256Field::new(U256::from(3));
-            let two = S256Field::new(U256::from(2));
-            let zero = S256Field::new(U256::from(0));
-
-            // 接線が垂直
-            if y1 == zero {
-                S256Point::new(None, None).unwrap();
-            }
+    fn one(&self) -> Self {
+        Self::ONE
+    }
 
-            let s = (three * x1.pow(2) + self.a) / (two * y1);
-            // s^2 - x1 - x2 (分配法則が成り立つので、-(x1+x2)としている)
-            let x3 = s.pow(2) - x1_add_x2;
-            let y3 = (s * (x1 - x3)) - y1;
-            
-            S256Point::new(Some(x3), Some(y3)).unwrap()
-        } else {
-            // x値の異なる点同士の加算
-
-            let s = y1_sub_y2 / x1_sub_x2;
-            // s^2 - x1 - x2 (分配法則が成り立つので、-(x1+x2)としている)
-            let x3 = s.pow(2) - x1_add_x2;
-            let y3 = (s * (x1 - x3)) - y1;
-            
-            S256Point::new(Some(x3), Some(y3)).unwrap()
-        }
+    fn is_zero(&self) -> bool {
+        self.mont.is_zero()
     }
 
-    fn mul_op(&self, rhs: U256) -> S256Point<S256Field> {
-        let mut res = S256Point::new(None, None).unwrap();
+    fn characteristic(&self) -> U256 {
+        P
+    }
 
-        let mut coef = rhs / self.N;
-        let mut current = self.clone();
+    fn neg(&self) -> Self {
+        Self::ZERO.sub_op(*self)
+    }
 
-        while coef > U256::from(0) {
-            if coef & U256::from(1) == U256::from(1) {
-                res = res.add_op(&current);
-            }
-            current = current.add_op(&current);
-            coef >>= U256::from(1);
-        }
-        res
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() { return None }
+
+        Some(self.pow(-1))
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.add_op(rhs))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.sub_op(rhs))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.mul_op(rhs))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        if rhs.is_zero() { return Err(ExpressionError::ZeroDivision) }
+
+        Ok(self.div_op(rhs))
+    }
+
+    fn square(&self) -> Self {
+        S256MontField { mont: P_MONTGOMERY.square(self.mont) }
+    }
+
+    fn double(&self) -> Self {
+        self.add_op(*self)
     }
 }
 
-impl ops::Add<&S256Point<S256Field>> for &S256Point<S256Field> {
-    type Output = S256Point<S256Field>;
+impl PrimeField for S256MontField {
+    fn from_u256(num: U256, prime: U256) -> Result<Self, ExpressionError> {
+        if prime != P { return Err(ExpressionError::DifferentOrderExpression) }
 
-    fn add(self, rhs: &S256Point<S256Field>) -> Self::Output {
+        Self::new(num)
+    }
+}
+
+impl ops::Add for S256MontField {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
         self.add_op(rhs)
     }
 }
 
-impl ops::Mul<U256> for &S256Point<S256Field> {
-    type Output = S256Point<S256Field>;
+impl ops::Sub for S256MontField {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_op(rhs)
+    }
+}
+
+impl ops::Mul for S256MontField {
+    type Output = Self;
 
-    fn mul(self, rhs: U256) -> Self::Output {
+    fn mul(self, rhs: Self) -> Self::Output {
         self.mul_op(rhs)
     }
 }
 
-impl ops::Mul<&S256Point<S256Field>> for U256 {
-    type Output = S256Point<S256Field>;
+impl ops::Div for S256MontField {
+    type Output = Self;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_op(rhs)
+    }
+}
+
+impl_field_num_traits!([] S256MontField);
+
+impl num_traits::Num for S256MontField {
+    type FromStrRadixErr = ExpressionError;
+
+    fn from_str_radix(str: &str, radix: u32) -> Result<Self, Self::FromStrRadixErr> {
+        match U256::from_str_radix(str, radix) {
+            Ok(num) => Self::new(num % P),
+            Err(_) => Err(ExpressionError::InvalidFieldElement),
+        }
+    }
+}
 
-    fn mul(self, rhs: &S256Point<S256Field>) -> Self::Output {
-        rhs * self
+impl std::fmt::Display for S256MontField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:064}", self.num())
     }
-}
\ No newline at end of file
+}
+
+// 生成点の位数nを法とするスカラー (秘密鍵や秘密分散の係数)
+// 値によって実行時間が変わらないように、加減算は分岐せずに正規化し、
+// 乗算はモンゴメリ乗算、逆元は公開の指数 n-2 による冪で計算する
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct S256Scalar {
+    mont: U256,
+}
+
+impl S256Scalar {
+    pub const ZERO: Self = S256Scalar { mont: U256::zero() };
+    pub const ONE: Self = S256Scalar { mont: U256([0x402DA1732FC9BEBF, 0x4551231950B75FC4, 0x0000000000000001, 0]) };
+
+    pub fn new(num: U256) -> Result<Self, ExpressionError> {
+        if num >= N { return Err(ExpressionError::InvalidFieldElement) }
+
+        Ok(S256Scalar { mont: N_MONTGOMERY.to_montgomery(num) })
+    }
+
+    // 通常の表現の値
+    pub fn num(&self) -> U256 {
+        N_MONTGOMERY.from_montgomery(self.mont)
+    }
+}
+
+impl FieldElementOperation for S256Scalar {
+    type GeneralOpOutput = Self;
+
+    fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        let (sum, carry) = add_limbs(&self.mont.0, &rhs.mont.0);
+
+        S256Scalar { mont: normalize_mod(sum, carry, N) }
+    }
+
+    fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        let (diff, borrow) = sub_limbs(&self.mont.0, &rhs.mont.0);
+        let n = ct::select_u256(U256::zero(), N, borrow == 1);
+
+        S256Scalar { mont: U256(add_limbs(&diff, &n.0).0) }
+    }
+
+    fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        S256Scalar { mont: N_MONTGOMERY.mul(self.mont, rhs.mont) }
+    }
+
+    // 結果を返せないので、0で割った場合はpanicする (Errが必要ならtry_divを使う)
+    fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if rhs.is_zero() { panic!("{}", ExpressionError::ZeroDivision) }
+
+        self.mul_op(rhs.pow(-1))
+    }
+
+    // 冪の計算時間は指数のビットに依存するので、指数は公開の値にする
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
+        let ex = rhs.into().reduce(N - 1);
+
+        S256Scalar { mont: N_MONTGOMERY.pow(self.mont, ex) }
+    }
+}
+
+impl Field for S256Scalar {
+    fn zero(&self) -> Self {
+        Self::ZERO
+    }
+
+    fn one(&self) -> Self {
+        Self::ONE
+    }
+
+    fn is_zero(&self) -> bool {
+        ct::eq_u256(self.mont, U256::zero())
+    }
+
+    fn characteristic(&self) -> U256 {
+        N
+    }
+
+    fn neg(&self) -> Self {
+        Self::ZERO.sub_op(*self)
+    }
+
+    fn inv(&self) -> Option<Self> {
+        if self.is_zero() { return None }
+
+        Some(self.pow(-1))
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.add_op(rhs))
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.sub_op(rhs))
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        Ok(self.mul_op(rhs))
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        if rhs.is_zero() { return Err(ExpressionError::ZeroDivision) }
+
+        Ok(self.div_op(rhs))
+    }
+
+    fn square(&self) -> Self {
+        S256Scalar { mont: N_MONTGOMERY.square(self.mont) }
+    }
+
+    fn double(&self) -> Self {
+        self.add_op(*self)
+    }
+}
+
+// すべての点で共有するsecp256k1の曲線
+pub fn curve() -> Arc<Curve<S256Field>> {
+    static CURVE: OnceLock<Arc<Curve<S256Field>>> = OnceLock::new();
+    Arc::clone(CURVE.get_or_init(|| Arc::new(Curve::from_params(&CurveParams::secp256k1()).unwrap())))
+}
+
+// secp256k1上の点 (演算は汎用のPointを使う)
+pub type S256Point = Point<S256Field>;
+
+pub fn generator() -> S256Point {
+    Point::generator(&curve()).unwrap()
+}
+
+impl Point<S256Field> {
+    // 秘密鍵やnonceとの乗算用
+    // 全256bitについて同じ演算を行い、秘密のビットで分岐しない (モンゴメリラダー)
+    pub fn mul_ct(&self, secret: U256) -> S256Point {
+        let mut r0 = CompletePoint::IDENTITY;
+        let mut r1 = CompletePoint::from_affine(self);
+
+        for i in (0..256).rev() {
+            let bit = secret.bit(i);
+            CompletePoint::conditional_swap(&mut r0, &mut r1, bit);
+            r1 = r0.add(&r1);
+            r0 = r0.add(&r0);
+            CompletePoint::conditional_swap(&mut r0, &mut r1, bit);
+        }
+        r0.to_affine()
+    }
+
+    // SEC形式: 圧縮 0x02/0x03 + x (33バイト)、非圧縮 0x04 + x + y (65バイト)
+    // 無限遠点は座標を持たないので 0x00 の1バイトで表す
+    pub fn serialize(&self, compressed: bool) -> Vec<u8> {
+        let (x, y) = match self.coordinates() {
+            Coordinates::Infinity => return vec![0x00],
+            Coordinates::Affine { x, y } => (x, y),
+        };
+
+        let mut bytes = Vec::with_capacity(if compressed { 33 } else { 65 });
+        if compressed {
+            bytes.push(if y.num.bit(0) { 0x03 } else { 0x02 });
+            bytes.extend_from_slice(&x.num.to_big_endian());
+        } else {
+            bytes.push(0x04);
+            bytes.extend_from_slice(&x.num.to_big_endian());
+            bytes.extend_from_slice(&y.num.to_big_endian());
+        }
+        bytes
+    }
+
+    // 長さ・先頭バイト・座標の範囲が正しくないか、曲線上にない点ならErr(InvalidPoint)
+    pub fn parse(bytes: &[u8]) -> Result<Self, ExpressionError> {
+        let coord = |b: &[u8]| S256Field::new(U256::from_big_endian(b)).map_err(|_| ExpressionError::InvalidPoint);
+
+        match (bytes.first(), bytes.len()) {
+            (Some(0x00), 1) => Ok(Point::infinity(&curve())),
+            (Some(0x04), 65) => Point::new(coord(&bytes[1..33])?, coord(&bytes[33..])?, &curve()),
+            (Some(&prefix @ (0x02 | 0x03)), 33) => {
+                // y^2 = x^3 + 7 から y を復元し、偶奇を先頭バイトに合わせる
+                let x = coord(&bytes[1..])?;
+                let y = (x.square() * x + S256Field::from_reduced(U256::from(7))).sqrt().ok_or(ExpressionError::InvalidPoint)?;
+                let y = if y.num.bit(0) == (prefix == 0x03) { y } else { Field::neg(&y) };
+                Point::new(x, y, &curve())
+            }
+            _ => Err(ExpressionError::InvalidPoint),
+        }
+    }
+}
+
+// 射影座標 (X:Y:Z) = (X/Z, Y/Z)、無限遠点は (0:1:0)
+// a = 0 の曲線の完全加算公式 (Renes-Costello-Batina 2016, Algorithm 7) は
+// 無限遠点や同じ点同士の場合分けが不要なので、分岐せずに計算できる
+#[derive(Debug, Clone, Copy)]
+struct CompletePoint {
+    x: S256Field,
+    y: S256Field,
+    z: S256Field,
+}
+
+impl CompletePoint {
+    const IDENTITY: Self = CompletePoint { x: S256Field::ZERO, y: S256Field::ONE, z: S256Field::ZERO };
+    // 3b = 21
+    const B3: S256Field = S256Field::from_reduced(U256([21, 0, 0, 0]));
+
+    fn from_affine(point: &S256Point) -> Self {
+        match point.coordinates() {
+            Coordinates::Affine { x, y } => CompletePoint { x, y, z: S256Field::ONE },
+            Coordinates::Infinity => Self::IDENTITY,
+        }
+    }
+
+    fn add(&self, rhs: &Self) -> Self {
+        let (x1, y1, z1) = (self.x, self.y, self.z);
+        let (x2, y2, z2) = (rhs.x, rhs.y, rhs.z);
+
+        let t0 = x1 * x2;
+        let t1 = y1 * y2;
+        let t2 = z1 * z2;
+        let t3 = (x1 + y1) * (x2 + y2) - (t0 + t1);
+        let t4 = (y1 + z1) * (y2 + z2) - (t1 + t2);
+        let y3 = (x1 + z1) * (x2 + z2) - (t0 + t2);
+        let t0 = t0.double() + t0;
+        let t2 = Self::B3 * t2;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = Self::B3 * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = y3 * t0 + t1 * z3;
+        let z3 = z3 * t4 + t0 * t3;
+
+        CompletePoint { x: x3, y: y3, z: z3 }
+    }
+
+    fn conditional_swap(a: &mut Self, b: &mut Self, choice: bool) {
+        S256Field::conditional_swap(&mut a.x, &mut b.x, choice);
+        S256Field::conditional_swap(&mut a.y, &mut b.y, choice);
+        S256Field::conditional_swap(&mut a.z, &mut b.z, choice);
+    }
+
+    fn to_affine(self) -> S256Point {
+        // 逆元の指数p-2は公開されているので、powは秘密に依存しない
+        match self.z.inv() {
+            Some(z_inv) => Point::new(self.x * z_inv, self.y * z_inv, &curve()).unwrap(),
+            None => Point::infinity(&curve()),
+        }
+    }
+}
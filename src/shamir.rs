@@ -0,0 +1,135 @@
+use std::collections::BTreeMap;
+use primitive_types::U256;
+use crate::field_element::ExpressionError;
+use crate::hash::hash256;
+use crate::poly::Polynomial;
+use crate::secp256k1::{S256Scalar, N};
+
+// シェアのバイト列: index (1バイト) + value (32バイト, ビッグエンディアン) + checksum (4バイト)
+pub const SHARE_LEN: usize = 37;
+
+// t-of-n のシェア (x = index での多項式の値)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Share {
+    pub index: u8,
+    pub value: U256,
+}
+
+impl Share {
+    pub fn serialize(&self) -> [u8; SHARE_LEN] {
+        let mut bytes = [0u8; SHARE_LEN];
+        bytes[0] = self.index;
+        bytes[1..33].copy_from_slice(&self.value.to_big_endian());
+        let checksum = hash256(&bytes[..33]);
+        bytes[33..].copy_from_slice(&checksum[..4]);
+        bytes
+    }
+
+    // 長さ・チェックサム・値の範囲が正しくなければErr(InvalidShare)
+    pub fn parse(bytes: &[u8]) -> Result<Self, ExpressionError> {
+        if bytes.len() != SHARE_LEN { return Err(ExpressionError::InvalidShare) }
+        if hash256(&bytes[..33])[..4] != bytes[33..] { return Err(ExpressionError::InvalidShare) }
+
+        let share = Share { index: bytes[0], value: U256::from_big_endian(&bytes[1..33]) };
+        share.validate()?;
+        Ok(share)
+    }
+
+    fn validate(&self) -> Result<(), ExpressionError> {
+        if self.index == 0 || self.value >= N { return Err(ExpressionError::InvalidShare) }
+        Ok(())
+    }
+
+    fn point(&self) -> Result<(S256Scalar, S256Scalar), ExpressionError> {
+        Ok((S256Scalar::new(U256::from(self.index))?, S256Scalar::new(self.value)?))
+    }
+}
+
+// 1 <= secret < N の秘密鍵を t-of-n のシェアに分割する
+// 係数はOSの乱数 (/dev/urandom) から作るので、Unix以外ではErr(RandomUnavailable)になる
+// (その場合はsplit_withで乱数を与える)
+pub fn split(secret: &[u8; 32], threshold: u8, shares: u8) -> Result<Vec<Share>, ExpressionError> {
+    split_with(secret, threshold, shares, os_random)
+}
+
+// fillで乱数を与える版 (fillのErrはそのまま返す)
+pub fn split_with<R: FnMut(&mut [u8; 32]) -> Result<(), ExpressionError>>(secret: &[u8; 32], threshold: u8, shares: u8, fill: R) -> Result<Vec<Share>, ExpressionError> {
+    let poly = random_polynomial(secret, threshold, shares, fill)?;
+    deal(&poly, shares)
+}
+
+// 定数項がsecretで、それ以外の係数がランダムな threshold - 1 次の多項式
+// 係数は秘密なので、定数時間で計算するS256Scalarで持つ
+pub(crate) fn random_polynomial<R: FnMut(&mut [u8; 32]) -> Result<(), ExpressionError>>(secret: &[u8; 32], threshold: u8, shares: u8, mut fill: R) -> Result<Polynomial<S256Scalar>, ExpressionError> {
+    if threshold == 0 || threshold > shares { return Err(ExpressionError::InvalidThreshold) }
+
+    let secret = U256::from_big_endian(secret);
+    if secret.is_zero() || secret >= N { return Err(ExpressionError::InvalidFieldElement) }
+
+    let mut coeffs = vec![S256Scalar::new(secret)?];
+    for _ in 1..threshold {
+        // 偏りが出ないように、N以上なら引き直す
+        let mut buf = [0u8; 32];
+        let value = loop {
+            fill(&mut buf)?;
+            let value = U256::from_big_endian(&buf);
+            if value < N { break value }
+        };
+        coeffs.push(S256Scalar::new(value)?);
+    }
+    Ok(Polynomial::new(coeffs))
+}
+
+pub(crate) fn deal(poly: &Polynomial<S256Scalar>, shares: u8) -> Result<Vec<Share>, ExpressionError> {
+    (1..=shares)
+        .map(|index| Ok(Share { index, value: poly.eval(S256Scalar::new(U256::from(index))?)?.num() }))
+        .collect()
+}
+
+// threshold個以上のシェアから秘密鍵を復元する
+// thresholdより多く与えられた場合は、残りのシェアが同じ多項式に乗っているかを確認して
+// 食い違っていればErr(InconsistentShares)を返す (ちょうどthreshold個では検出できない)
+pub fn recover(shares: &[Share], threshold: u8) -> Result<[u8; 32], ExpressionError> {
+    if threshold == 0 { return Err(ExpressionError::InvalidThreshold) }
+
+    // 同じindexのシェアは値も同じでなければならない
+    let mut unique = BTreeMap::new();
+    for share in shares {
+        share.validate()?;
+        if *unique.entry(share.index).or_insert(share.value) != share.value {
+            return Err(ExpressionError::InconsistentShares);
+        }
+    }
+    if unique.len() < threshold as usize { return Err(ExpressionError::NotEnoughShares) }
+
+    let points = unique.iter()
+        .map(|(&index, &value)| Share { index, value }.point())
+        .collect::<Result<Vec<_>, _>>()?;
+    let (base, rest) = points.split_at(threshold as usize);
+
+    let poly = Polynomial::interpolate(base)?;
+    for &(x, y) in rest {
+        if poly.eval(x)? != y { return Err(ExpressionError::InconsistentShares) }
+    }
+
+    let secret = poly.eval(S256Scalar::ZERO)?.num();
+    // 正しいシェアから0が復元されることはない
+    if secret.is_zero() { return Err(ExpressionError::InconsistentShares) }
+    Ok(secret.to_big_endian())
+}
+
+// /dev/urandom が無い環境ではErr(RandomUnavailable)
+#[cfg(unix)]
+pub(crate) fn os_random(buf: &mut [u8; 32]) -> Result<(), ExpressionError> {
+    use std::io::Read;
+
+    std::fs::File::open("/dev/urandom")
+        .and_then(|mut f| f.read_exact(buf))
+        .map_err(|e| ExpressionError::RandomUnavailable(e.kind()))
+}
+
+// 外部クレートを使わずにOSの乱数を読む方法が無い
+#[cfg(not(unix))]
+pub(crate) fn os_random(_buf: &mut [u8; 32]) -> Result<(), ExpressionError> {
+    Err(ExpressionError::RandomUnavailable(std::io::ErrorKind::Unsupported))
+}
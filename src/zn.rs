@@ -0,0 +1,160 @@
+use std::ops;
+use primitive_types::U256;
+use crate::field_element::{pow_unreduced, ExpressionError, Exponent, Field, FieldElementOperation};
+use crate::field_int::FieldInt;
+use crate::gcd::ext_gcd;
+
+// 合成数nを法とする剰余環 Z/nZ の元
+// 逆元が存在しない場合は、割る数とnの最大公約数 (nの非自明な約数) をErr(NonInvertible)で返す
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ZnElement<T: FieldInt> {
+    pub num: T,
+    pub modulus: T,
+}
+
+impl<T: FieldInt> ZnElement<T> {
+    pub fn new(num: T, modulus: T) -> Result<Self, ExpressionError> {
+        if num >= modulus || num < T::zero() {
+            return Err(ExpressionError::InvalidFieldElement);
+        }
+
+        Ok(ZnElement { num, modulus })
+    }
+
+    // 逆元が存在すればOk、存在しなければgcd(num, n)を持つErr
+    pub fn try_inv(&self) -> Result<Self, ExpressionError> {
+        if self.is_zero() { return Err(ExpressionError::ZeroDivision) }
+
+        let (g, t) = ext_gcd(self.num.to_u256(), self.modulus.to_u256());
+        if g != U256::one() { return Err(ExpressionError::NonInvertible(g)) }
+
+        Ok(Self { num: T::from_u256(t), modulus: self.modulus })
+    }
+}
+
+impl<T: FieldInt> FieldElementOperation for ZnElement<T> {
+    type GeneralOpOutput = Result<Self, ExpressionError>;
+
+    fn add_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if self.modulus != rhs.modulus { return Err(ExpressionError::DifferentOrderExpression) }
+
+        Ok(Self { num: self.num.add_mod(rhs.num, self.modulus), modulus: self.modulus })
+    }
+
+    fn sub_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if self.modulus != rhs.modulus { return Err(ExpressionError::DifferentOrderExpression) }
+
+        Ok(Self { num: self.num.sub_mod(rhs.num, self.modulus), modulus: self.modulus })
+    }
+
+    fn mul_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if self.modulus != rhs.modulus { return Err(ExpressionError::DifferentOrderExpression) }
+
+        Ok(Self { num: self.num.mul_mod(rhs.num, self.modulus), modulus: self.modulus })
+    }
+
+    fn div_op(self, rhs: Self) -> Self::GeneralOpOutput {
+        if self.modulus != rhs.modulus { return Err(ExpressionError::DifferentOrderExpression) }
+
+        self.mul_op(rhs.try_inv()?)
+    }
+
+    // 群の位数が分からないので指数は還元しない
+    // 負の指数で逆元が存在しない場合は0を返す
+    fn pow<E: Into<Exponent>>(self, rhs: E) -> Self {
+        pow_unreduced(self, rhs.into())
+    }
+}
+
+// Pointで使えるようにFieldを実装するが、体ではないので
+// try_divは逆元が存在しない場合にErr(NonInvertible)を返す
+impl<T: FieldInt> Field for ZnElement<T> {
+    fn zero(&self) -> Self {
+        Self { num: T::zero(), modulus: self.modulus }
+    }
+
+    fn one(&self) -> Self {
+        // n = 1 の場合は 0 = 1
+        Self { num: T::one().mul_mod(T::one(), self.modulus), modulus: self.modulus }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.num.is_zero()
+    }
+
+    fn characteristic(&self) -> U256 {
+        self.modulus.to_u256()
+    }
+
+    fn neg(&self) -> Self {
+        Self { num: T::zero().sub_mod(self.num, self.modulus), modulus: self.modulus }
+    }
+
+    fn inv(&self) -> Option<Self> {
+        self.try_inv().ok()
+    }
+
+    fn try_add(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.add_op(rhs)
+    }
+
+    fn try_sub(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.sub_op(rhs)
+    }
+
+    fn try_mul(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.mul_op(rhs)
+    }
+
+    fn try_div(self, rhs: Self) -> Result<Self, ExpressionError> {
+        self.div_op(rhs)
+    }
+
+    fn square(&self) -> Self {
+        Self { num: self.num.mul_mod(self.num, self.modulus), modulus: self.modulus }
+    }
+
+    fn double(&self) -> Self {
+        Self { num: self.num.add_mod(self.num, self.modulus), modulus: self.modulus }
+    }
+}
+
+impl<T: FieldInt> ops::Add for ZnElement<T> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.add_op(rhs)
+    }
+}
+
+impl<T: FieldInt> ops::Sub for ZnElement<T> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.sub_op(rhs)
+    }
+}
+
+impl<T: FieldInt> ops::Mul for ZnElement<T> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.mul_op(rhs)
+    }
+}
+
+impl<T: FieldInt> ops::Div for ZnElement<T> {
+    type Output = Result<Self, ExpressionError>;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        self.div_op(rhs)
+    }
+}
+
+impl<T: FieldInt> ops::Neg for ZnElement<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Field::neg(&self)
+    }
+}